@@ -0,0 +1,64 @@
+use crate::dates;
+use crate::gtd;
+use crate::gtd::Priority;
+use crate::gtd::Task;
+use crate::EResult;
+
+/// A task captured from a line of shorthand, along with the name of the
+/// project it should go to
+pub struct Capture
+{
+    pub task: Task,
+    pub project: Option<String>,
+}
+
+/// Parse a line of capture shorthand into a task
+///
+/// Words can be `@context`, `+project`, `due:date`, `!priority` and `#tag`;
+/// every other word is part of the task's name. For example,
+/// `call bob @phone +website due:fri !high` creates the task
+/// "call bob".
+pub fn parse_shorthand(line: &str) -> EResult<Capture>
+{
+    let mut name: Vec<&str> = vec![];
+    let mut task = Task::new(String::new(), None);
+    let mut project: Option<String> = None;
+
+    for word in line.split_whitespace()
+    {
+        if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty())
+        {
+            task.contexts.push(gtd::parse_context(context));
+        }
+        else if let Some(tag) =
+            word.strip_prefix('#').filter(|t| !t.is_empty())
+        {
+            task.tags.push(gtd::parse_tag(tag));
+        }
+        else if let Some(name) =
+            word.strip_prefix('+').filter(|p| !p.is_empty())
+        {
+            project = Some(name.replace(['-', '_'], " ").to_lowercase());
+        }
+        else if let Some(priority) =
+            word.strip_prefix('!').filter(|p| !p.is_empty())
+        {
+            task.priority = Some(Priority::parse(priority)?);
+        }
+        else if let Some(due) = word.strip_prefix("due:")
+        {
+            // Words can't hold spaces, so "due:next-monday" is allowed too
+            task.due = Some(dates::parse_date(due).or_else(|_| {
+                dates::parse_date(&due.replace(['-', '_'], " "))
+            })?);
+        }
+        else
+        {
+            name.push(word);
+        }
+    }
+
+    task.name = name.join(" ");
+
+    Ok(Capture { task, project })
+}
@@ -1,33 +1,50 @@
+use std::collections::BTreeMap;
 use std::io;
 use std::io::ErrorKind;
 use std::path;
 
+use chrono::NaiveDate;
 use colored::Colorize;
 
+use crate::capture;
+use crate::config;
+use crate::dates;
+use crate::editor;
 use crate::gtd;
+use crate::gtd::Area;
+use crate::gtd::ChecklistItem;
+use crate::gtd::Energy;
 use crate::gtd::File;
+use crate::gtd::Goal;
 use crate::gtd::List;
+use crate::gtd::ListKind;
+use crate::gtd::Priority;
 use crate::gtd::Project;
 use crate::gtd::Status;
 use crate::gtd::Task;
 use crate::indexer;
 use crate::itempath;
+use crate::prompt;
 use crate::tos;
 use crate::tos::OutputFormattable;
 use crate::EResult;
+use crate::ItemKind;
+use crate::ProjectEditArgs;
+use crate::SortOrder;
+use crate::TaskEditArgs;
 
 use crate::gtd::ListContainer;
 use crate::gtd::ProjectContainer;
+use crate::gtd::Tagged;
 use crate::gtd::TaskContainer;
 
 pub fn write_workspace_defaults(path: &str) -> EResult<()>
 {
     let basic_structure = File {
-        lists: vec![
-            gtd::List::new("inbox".to_owned()),
-            gtd::List::new("next".to_owned()),
-            gtd::List::new("done".to_owned()),
-        ],
+        lists: config::get().default_lists()?,
+        areas: vec![],
+        goals: vec![],
+        next_id: 0,
     };
 
     basic_structure.write_to_file(path)?;
@@ -45,6 +62,13 @@ pub fn reset_workspace(path: &str) -> EResult<()>
         )));
     }
 
+    if !prompt::confirm("Remove every item of the workspace?")?
+    {
+        tos::send_info("Nothing was removed.");
+
+        return Ok(());
+    }
+
     write_workspace_defaults(path)?;
 
     tos::send_success("The workspace has been reset.");
@@ -69,40 +93,173 @@ pub fn initialize_workspace(path: &str) -> EResult<()>
     Ok(())
 }
 
-pub fn create_task(
-    file: &mut File,
-    path: String,
-    name: String,
-    description: Option<String>,
-) -> EResult<()>
+pub fn create_task(file: &mut File, path: String, task: Task) -> EResult<()>
 {
     let task_path = itempath::ContainerPath::parse(&path)?;
 
-    let list = file.get_list_mut_forced(&task_path.list_name)?;
+    let container = file.get_container_mut(&task_path)?;
+
+    container.task_exists_forced(&task.name)?;
+
+    let name = task.name.clone();
+
+    let mut dates: Vec<String> = vec![];
 
-    if let Some(project_index) = task_path.project_index
+    if let Some(due) = &task.due
     {
-        let project = list.get_project_mut_forced(project_index)?;
+        dates.push(tos::format_due_date(due));
+    }
+
+    if let Some(tickler) = &task.tickler
+    {
+        dates.push(tos::format_tickler(tickler));
+    }
 
-        project.task_exists_forced(&name)?;
+    container.push_task(task);
 
-        let task = Task::new(name.clone(), description.clone());
+    tos::send_success(&format!(
+        "Task {} created at {}{}.",
+        &name,
+        &task_path.tos_format(),
+        if dates.is_empty()
+        {
+            String::new()
+        }
+        else
+        {
+            format!(" ({})", dates.join(", "))
+        }
+    ));
 
-        project.push_task(task);
+    Ok(())
+}
+
+/// Parse an optional field of an `edit` command, where "none" clears it
+fn parse_clearable<T>(
+    source: &Option<String>,
+    parse: impl Fn(&str) -> EResult<T>,
+) -> EResult<Option<Option<T>>>
+{
+    match source.as_deref()
+    {
+        None => Ok(None),
+        Some(value) if value.is_empty() || value.to_lowercase() == "none" =>
+        {
+            Ok(Some(None))
+        }
+        Some(value) => Ok(Some(Some(parse(value)?))),
     }
-    else
+}
+
+pub fn edit_task(file: &mut File, args: &TaskEditArgs) -> EResult<()>
+{
+    let task_path = itempath::TaskPath::parse(&args.path)?;
+
+    let description = parse_clearable(&args.description, |d| Ok(d.into()))?;
+    let priority = parse_clearable(&args.priority, Priority::parse)?;
+    let due = parse_clearable(&args.due, dates::parse_date)?;
+    let tickler = parse_clearable(&args.tickler, dates::parse_date)?;
+    let delegate =
+        parse_clearable(&args.delegate, |d| Ok(gtd::parse_delegate(d)))?;
+    let follow_up = parse_clearable(&args.follow_up, dates::parse_date)?;
+    let recurrence = parse_clearable(&args.recur, |r| r.parse())?;
+    let estimate = parse_clearable(&args.estimate, dates::parse_duration)?;
+    let energy = parse_clearable(&args.energy, Energy::parse)?;
+    let contexts = match args.context.as_slice()
+    {
+        [] => None,
+        [context] if context.to_lowercase() == "none" => Some(vec![]),
+        contexts =>
+        {
+            Some(contexts.iter().map(|c| gtd::parse_context(c)).collect())
+        }
+    };
+
+    let container = file.get_container_mut(&task_path.container())?;
+
+    let current = container.get_task_forced(task_path.task_index)?;
+
+    if let Some(name) =
+        args.name.as_ref().filter(|name| **name != current.name)
+    {
+        container.task_exists_forced(name)?;
+    }
+
+    let task = container.get_task_mut_forced(task_path.task_index)?;
+
+    if let Some(name) = &args.name
+    {
+        task.name = name.clone();
+    }
+
+    if let Some(description) = description
+    {
+        task.description = description;
+    }
+
+    if let Some(priority) = priority
+    {
+        task.priority = priority;
+    }
+
+    if let Some(due) = due
+    {
+        task.due = due;
+    }
+
+    if let Some(tickler) = tickler
+    {
+        task.tickler = tickler;
+    }
+
+    if let Some(delegate) = delegate
+    {
+        task.delegated = delegate.as_ref().map(|_| dates::today());
+        task.delegate = delegate;
+    }
+
+    if let Some(follow_up) = follow_up
     {
-        list.task_exists_forced(&name)?;
+        task.follow_up = follow_up;
+    }
+
+    if let Some(recurrence) = recurrence
+    {
+        task.recurrence = recurrence;
+    }
 
-        let task = Task::new(name.clone(), description.clone());
+    if let Some(estimate) = estimate
+    {
+        task.estimate = estimate;
+    }
+
+    if let Some(energy) = energy
+    {
+        task.energy = energy;
+    }
 
-        list.push_task(task);
+    if let Some(contexts) = contexts
+    {
+        task.contexts = contexts;
     }
 
+    let task = file.get_task(&task_path)?;
+
     tos::send_success(&format!(
-        "Task {} created at {}.",
-        &name,
+        "Task {} updated ({}{}).",
         &task_path.tos_format(),
+        tos::format_task(task, &file.open_blockers(task)),
+        match &task.follow_up
+        {
+            Some(date) =>
+            {
+                format!(
+                    " (follow up {})",
+                    tos::format_follow_up(date, dates::today())
+                )
+            }
+            None => String::new(),
+        }
     ));
 
     Ok(())
@@ -112,34 +269,23 @@ pub fn remove_task(file: &mut File, path: String) -> EResult<()>
 {
     let task_path = itempath::TaskPath::parse(&path)?;
 
-    let list = file.get_list_mut_forced(&task_path.list_name)?;
+    let container = file.get_container_mut(&task_path.container())?;
 
-    if let Some(project_index) = task_path.project_index
-    {
-        let project = list.get_project_mut_forced(project_index)?;
-
-        let task_name =
-            project.get_task_forced(task_path.task_index)?.name.clone();
+    let task_name = container
+        .get_task_forced(task_path.task_index)?
+        .name
+        .clone();
 
-        project.remove_task(task_path.task_index);
+    if !prompt::confirm(&format!("Remove task {}?", &path))?
+    {
+        tos::send_info("Nothing was removed.");
 
-        tos::send_success(&format!(
-            "Task {} ({}) removed.",
-            &path, &task_name
-        ));
+        return Ok(());
     }
-    else
-    {
-        let task_name =
-            list.get_task_forced(task_path.task_index)?.name.clone();
 
-        list.remove_task(task_path.task_index);
+    container.remove_task(task_path.task_index);
 
-        tos::send_success(&format!(
-            "Task {} ({}) removed.",
-            &path, &task_name
-        ));
-    };
+    tos::send_success(&format!("Task {} ({}) removed.", &path, &task_name));
 
     Ok(())
 }
@@ -148,21 +294,38 @@ pub fn mark_task(
     file: &mut File,
     path: &str,
     new_status: Status,
+    force: bool,
 ) -> EResult<()>
 {
     let task_path = itempath::TaskPath::parse(path)?;
 
-    let list = file.get_list_mut_forced(&task_path.list_name)?;
-
-    let task = if let Some(project_index) = task_path.project_index
+    if let Status::DONE = new_status
     {
-        list.get_project_mut_forced(project_index)?
-            .get_task_mut_forced(task_path.task_index)?
+        let task = file.get_task(&task_path)?;
+        let blockers = file.open_blockers(task);
+
+        if !blockers.is_empty() && !force
+        {
+            let names: Vec<&str> = blockers
+                .iter()
+                .map(|blocker| blocker.name.as_str())
+                .collect();
+
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Task is waiting on {} (use --force to mark it anyway).",
+                    names.join(", ")
+                ),
+            )));
+        }
     }
-    else
-    {
-        list.get_task_mut_forced(task_path.task_index)?
-    };
+
+    let container = file.get_container_mut(&task_path.container())?;
+
+    let task = container.get_task_mut_forced(task_path.task_index)?;
+
+    let was_done = task.done();
 
     task.status = new_status;
 
@@ -173,427 +336,1975 @@ pub fn mark_task(
         tos::format_status(&new_status),
     ));
 
-    Ok(())
-}
-
-pub fn move_task(file: &mut File, source: &str, target: &str) -> EResult<()>
-{
-    let source_path = itempath::TaskPath::parse(source)?;
-    let target_path = itempath::ContainerPath::parse(target)?;
-
-    let source_list = file.get_list_mut_forced(&source_path.list_name)?;
+    if was_done || !task.done()
+    {
+        return Ok(());
+    }
 
-    let task = if let Some(project_index) = source_path.project_index
+    if let Some(next_task) = task.next_occurrence(dates::today())
     {
-        let project = source_list.get_project_mut_forced(project_index)?;
+        let next_due = next_task.due;
 
-        project.get_task_forced(source_path.task_index)?;
+        container.push_task(next_task);
 
-        project.remove_task(source_path.task_index)
+        tos::send_success(&format!(
+            "Next occurrence created at {}{}.",
+            &task_path
+                .container()
+                .task(container.tasks().len() - 1)
+                .tos_format(),
+            match next_due
+            {
+                Some(due) => format!(" ({})", tos::format_due_date(&due)),
+                None => String::new(),
+            },
+        ));
     }
-    else
-    {
-        source_list.get_task_forced(source_path.task_index)?;
 
-        source_list.remove_task(source_path.task_index)
-    };
+    Ok(())
+}
 
-    let task_name = task.name.clone();
+pub fn add_task_dependency(
+    file: &mut File,
+    path: &str,
+    blocker_path: &str,
+) -> EResult<()>
+{
+    let task_path = itempath::TaskPath::parse(path)?;
+    let blocker_path = itempath::TaskPath::parse(blocker_path)?;
 
-    let target_list = file.get_list_mut_forced(&target_path.list_name)?;
+    let task_id = file.task_id(&task_path)?;
+    let blocker_id = file.task_id(&blocker_path)?;
 
-    let new_index = if let Some(project_index) = target_path.project_index
+    if task_id == blocker_id
     {
-        let project = target_list.get_project_mut_forced(project_index)?;
-
-        project.push_task(task);
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "A task can not depend on itself.",
+        )));
+    }
 
-        project.tasks().len() - 1
+    if file.depends_on(blocker_id, task_id)
+    {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "This dependency would create a dependency cycle.",
+        )));
     }
-    else
+
+    let task = file.get_task_mut(&task_path)?;
+
+    if task.depends_on.contains(&blocker_id)
     {
-        target_list.push_task(task);
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "Task already depends on that task.",
+        )));
+    }
 
-        target_list.tasks().len() - 1
-    };
+    task.depends_on.push(blocker_id);
 
     tos::send_success(&format!(
-        "Moved task {} to {}/{} ({}).",
-        &source_path.tos_format(),
-        &target_path.tos_format(),
-        tos::format_index(new_index),
-        task_name,
+        "Task {} now waits on {} ({}).",
+        &task_path.tos_format(),
+        &blocker_path.tos_format(),
+        tos::format_task_name(file.get_task(&blocker_path)?),
     ));
 
     Ok(())
 }
 
-pub fn move_project(file: &mut File, source: &str, target: &str)
-    -> EResult<()>
+pub fn remove_task_dependency(
+    file: &mut File,
+    path: &str,
+    blocker_path: &str,
+) -> EResult<()>
 {
-    let source_path = itempath::ContainerPath::parse(source)?;
+    let task_path = itempath::TaskPath::parse(path)?;
+    let blocker_path = itempath::TaskPath::parse(blocker_path)?;
 
-    let target_path = itempath::ContainerPath::parse(target)?;
+    let blocker_id = file.get_task(&blocker_path)?.id;
 
-    let source_index = match source_path.project_index
+    let task = file.get_task_mut(&task_path)?;
+
+    let position = match task
+        .depends_on
+        .iter()
+        .position(|id| blocker_id != 0 && *id == blocker_id)
     {
-        Some(index) => index,
+        Some(position) => position,
         None =>
         {
             return Err(Box::new(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Please specify a project in the source path.",
+                io::ErrorKind::NotFound,
+                "Task does not depend on that task.",
             )));
         }
     };
 
-    if target_path.project_index.is_some()
-    {
-        return Err(Box::new(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Can not move a project to another project.",
-        )));
-    }
-
-    let source_list = file.get_list_mut_forced(&source_path.list_name)?;
+    task.depends_on.remove(position);
 
-    source_list.get_project_forced(source_index)?;
+    tos::send_success(&format!(
+        "Task {} no longer waits on {}.",
+        &task_path.tos_format(),
+        &blocker_path.tos_format(),
+    ));
 
-    let project = source_list.remove_project(source_index);
+    Ok(())
+}
 
-    let project_name = project.name.clone();
+pub fn add_checklist_items(
+    file: &mut File,
+    path: &str,
+    items: Vec<String>,
+) -> EResult<()>
+{
+    let task_path = itempath::TaskPath::parse(path)?;
 
-    let project_status = project.status();
+    let task = file.get_task_mut(&task_path)?;
 
-    let target_list = file.get_list_mut_forced(&target_path.list_name)?;
+    let count = items.len();
 
-    target_list.push_project(project);
+    task.checklist
+        .extend(items.into_iter().map(ChecklistItem::new));
 
     tos::send_success(&format!(
-        "Project {} moved to {}/{} ({})",
-        &source_path.tos_format(),
-        &target_path.tos_format(),
-        tos::format_index(target_list.projects().len() - 1),
-        tos::format_project(&project_name, &project_status),
+        "{} checklist items added to task {} ({}).",
+        tos::format_number(count),
+        task_path.tos_format(),
+        tos::format_task_name(task),
     ));
 
     Ok(())
 }
 
-pub fn create_list(file: &mut File, name: String) -> EResult<()>
+pub fn check_checklist_item(
+    file: &mut File,
+    path: &str,
+    index: &str,
+    checked: bool,
+) -> EResult<()>
 {
-    if let Some(_) = file.lists.iter().find(|list: &&List| list.name == name)
-    {
-        return Err(Box::new(io::Error::new(
-            ErrorKind::AlreadyExists,
-            "List already exists.",
-        )));
-    }
+    let task_path = itempath::TaskPath::parse(path)?;
+    let index = indexer::identifier_to_index(index)?;
 
-    let list = List::new(name.clone());
+    let task = file.get_task_mut(&task_path)?;
 
-    let formatted_name = tos::format_list_name(&list.name);
+    let item = task.get_checklist_item_mut_forced(index)?;
 
-    file.lists.push(list);
+    item.checked = checked;
 
-    tos::send_success(&format!("List {formatted_name} created."));
+    let formatted_item = tos::format_checklist_item(item);
+
+    tos::send_success(&format!(
+        "Checklist item {} of task {} {} ({}).",
+        tos::format_index(index),
+        task_path.tos_format(),
+        if checked { "ticked" } else { "unticked" },
+        formatted_item,
+    ));
 
     Ok(())
 }
 
-pub fn remove_list(file: &mut File, name: &str) -> EResult<()>
+pub fn remove_checklist_item(
+    file: &mut File,
+    path: &str,
+    index: &str,
+) -> EResult<()>
 {
-    let index =
-        match file.lists.iter().position(|list: &List| list.name == name)
-        {
-            Some(index) => index,
-            None =>
-            {
-                return Err(Box::new(io::Error::new(
-                    ErrorKind::NotFound,
-                    "List not found.",
-                )));
-            }
-        };
+    let task_path = itempath::TaskPath::parse(path)?;
+    let index = indexer::identifier_to_index(index)?;
 
-    file.lists.remove(index);
+    let task = file.get_task_mut(&task_path)?;
+
+    let item_name = task.get_checklist_item_mut_forced(index)?.name.clone();
+
+    task.checklist.remove(index);
 
     tos::send_success(&format!(
-        "List {} removed.",
-        tos::format_list_name(&name)
+        "Checklist item {} ({}) removed from task {}.",
+        tos::format_index(index),
+        item_name,
+        task_path.tos_format(),
     ));
 
     Ok(())
 }
 
-pub fn show_list(file: &mut File, name: &str, all: bool) -> EResult<()>
+pub fn move_task(file: &mut File, source: &str, target: &str) -> EResult<()>
 {
-    let name = name.to_lowercase();
+    let source_path = itempath::TaskPath::parse(source)?;
+    let target_path = itempath::ContainerPath::parse(target)?;
 
-    let list = file.get_list_forced(&name)?;
+    let source_container = file.get_container_mut(&source_path.container())?;
 
-    let formatted_name = tos::format_list_name(&name);
+    source_container.get_task_forced(source_path.task_index)?;
 
-    if list.tasks().is_empty() && list.projects().is_empty()
-    {
-        tos::send_info(&format!("List {formatted_name} is empty."));
+    let task = source_container.remove_task(source_path.task_index);
 
-        return Ok(());
-    }
+    let task_name = task.name.clone();
 
-    let mut output = tos::OutputBlock::new();
+    let target_container = file.get_container_mut(&target_path)?;
 
-    output
-        .insert_line(
-            &format!(
-                "Contents of list {}",
-                formatted_name.color(tos::COLOR_IDENTIFIER)
-            ),
-            0,
-        )
-        .insert_text("\n");
+    target_container.push_task(task);
 
-    // TODO: Show the empty projects first
-    if !list.projects().is_empty()
-    {
-        output.insert_line(
-            &format!(
-                "{} {}",
-                tos::format_section_name("projects"),
-                tos::format_progress(
-                    list.projects_completed(),
-                    list.projects().len()
-                ),
-            ),
-            1,
-        );
+    tos::send_success(&format!(
+        "Moved task {} to {} ({}).",
+        &source_path.tos_format(),
+        &target_path
+            .task(target_container.tasks().len() - 1)
+            .tos_format(),
+        task_name,
+    ));
 
-        for (index, project) in list.projects().iter().enumerate()
-        {
-            output.insert_line(
-                &format!(
-                    "{}. {} {}",
-                    tos::format_index(index),
-                    tos::format_project(&project.name, &project.status()),
-                    if project.tasks().is_empty()
-                    {
-                        "(No tasks)".to_string()
-                    }
-                    else
-                    {
-                        tos::format_progress(
-                            project.tasks_completed(),
-                            project.tasks().len(),
-                        )
-                    },
-                ),
-                2,
-            );
+    Ok(())
+}
 
-            if all && !project.tasks().is_empty()
-            {
-                for (index, task) in project.tasks().iter().enumerate()
-                {
-                    output.insert_line(
-                        &format!(
-                            "{}. {}",
-                            // TODO: Use this function at the formatting func
-                            indexer::index_to_identifier(index)
-                                .color(tos::COLOR_NUM_VALUE),
-                            tos::format_task(&task)
-                        ),
-                        3,
-                    );
-                }
+pub fn delegate_task(
+    file: &mut File,
+    path: &str,
+    person: &str,
+    follow_up: Option<NaiveDate>,
+) -> EResult<()>
+{
+    let task_path = itempath::TaskPath::parse(path)?;
 
-                if index < list.projects().len() - 1
-                {
-                    output.insert_text("\n");
-                }
-            }
-        }
+    let person = gtd::parse_delegate(person);
 
-        output.insert_text("\n");
-    }
+    let task = file.get_task_mut(&task_path)?;
 
-    if !list.tasks().is_empty()
+    task.delegate = Some(person.clone());
+    task.delegated = Some(dates::today());
+    task.follow_up = follow_up;
+
+    let in_waiting_list = file
+        .get_list(&task_path.list_name)
+        .is_some_and(|list| list.kind == Some(ListKind::WaitingFor));
+
+    let new_path = if in_waiting_list
     {
-        output.insert_line(
-            &format!(
-                "{} {}",
-                tos::format_section_name("tasks"),
-                tos::format_progress(
-                    list.tasks_completed(),
-                    list.tasks().len()
-                ),
-            ),
-            1,
-        );
+        task_path
+    }
+    else
+    {
+        let task = file
+            .get_container_mut(&task_path.container())?
+            .remove_task(task_path.task_index);
 
-        for (index, task) in list.tasks().iter().enumerate()
-        {
-            output.insert_line(
-                &format!(
-                    "{}. {}",
-                    indexer::index_to_identifier(index)
-                        .color(tos::COLOR_NUM_VALUE),
-                    tos::format_task(&task),
-                ),
-                2,
-            );
+        let waiting_list =
+            file.list_of_kind_mut(ListKind::WaitingFor, "waiting");
+
+        waiting_list.push_task(task);
+
+        itempath::TaskPath {
+            list_name: waiting_list.name.clone(),
+            project_indexes: vec![],
+            task_index: waiting_list.tasks().len() - 1,
         }
-    }
+    };
 
-    output.send();
+    let task = file.get_task(&new_path)?;
+
+    tos::send_success(&format!(
+        "Task {} ({}) delegated to {}{}.",
+        new_path.tos_format(),
+        tos::format_task_name(task),
+        tos::format_delegate(&person),
+        match &task.follow_up
+        {
+            Some(date) =>
+            {
+                format!(
+                    ", following up on {}",
+                    tos::format_follow_up(date, dates::today())
+                )
+            }
+            None => String::new(),
+        }
+    ));
 
     Ok(())
 }
 
-pub fn show_project(file: &mut File, path: &str) -> EResult<()>
+pub fn move_project(file: &mut File, source: &str, target: &str)
+    -> EResult<()>
 {
-    let project_path = itempath::ContainerPath::parse(path)?;
+    let source_path = itempath::ContainerPath::parse(source)?;
+
+    let mut target_path = itempath::ContainerPath::parse(target)?;
 
-    let project_index = match project_path.project_index
+    let (source_parent, source_index) = match source_path.split_last()
     {
-        Some(index) => index,
+        Some(location) => location,
         None =>
         {
             return Err(Box::new(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "No project index provided.",
+                "Please specify a project in the source path.",
             )));
         }
     };
 
-    let list = file.get_list_forced(&project_path.list_name)?;
+    if target_path.starts_with(&source_path)
+    {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Can not move a project into itself.",
+        )));
+    }
 
-    let project = list.get_project_forced(project_index)?;
+    file.get_project(&source_path)?;
+    file.get_project_container(&target_path)?;
 
-    if project.tasks().is_empty()
-    {
-        tos::send_info(&format!(
-            "Project {} is empty.",
-            tos::format_project_name(&project.name, &project.status())
-        ));
+    // Removing the project shifts the indexes of its later siblings, which
+    // the target path may go through
+    let depth = source_parent.project_indexes.len();
 
-        return Ok(());
+    if target_path.starts_with(&source_parent)
+        && target_path.project_indexes.len() > depth
+        && target_path.project_indexes[depth] > source_index
+    {
+        target_path.project_indexes[depth] -= 1;
     }
 
-    let mut output = tos::OutputBlock::new();
+    let project = file
+        .get_project_container_mut(&source_parent)?
+        .remove_project(source_index);
 
-    output
-        .insert_line(
-            &format!(
-                "Contents of project {}",
-                tos::format_project(&project.name, &project.status()),
-            ),
-            0,
-        )
-        .insert_text("\n");
+    let formatted_project = tos::format_project(&project);
+
+    let target_container = file.get_project_container_mut(&target_path)?;
+
+    target_container.push_project(project);
 
-    for (index, task) in project.tasks().iter().enumerate()
+    tos::send_success(&format!(
+        "Project {} moved to {} ({})",
+        &source_path.tos_format(),
+        &target_path
+            .child(target_container.projects().len() - 1)
+            .tos_format(),
+        formatted_project,
+    ));
+
+    Ok(())
+}
+
+pub fn create_list(
+    file: &mut File,
+    name: String,
+    kind: Option<ListKind>,
+) -> EResult<()>
+{
+    if let Some(_) = file.lists.iter().find(|list: &&List| list.name == name)
     {
-        output.insert_line(
-            &format!(
-                "{}. {}",
-                indexer::index_to_identifier(index)
-                    .color(tos::COLOR_NUM_VALUE),
-                tos::format_task(&task)
-            ),
-            0,
-        );
+        return Err(Box::new(io::Error::new(
+            ErrorKind::AlreadyExists,
+            "List already exists.",
+        )));
     }
 
-    output.send();
+    let mut list = List::new(name.clone());
+    list.kind = kind;
+
+    let formatted_name = tos::format_list_name(&list.name);
+
+    file.lists.push(list);
+
+    tos::send_success(&format!("List {formatted_name} created."));
 
     Ok(())
 }
 
-pub fn create_project(
+pub fn edit_list(
     file: &mut File,
-    list_name: &str,
-    name: String,
+    name: &str,
+    new_name: Option<String>,
+    kind: &Option<String>,
 ) -> EResult<()>
 {
-    let name = name.to_lowercase();
+    let kind = parse_clearable(kind, ListKind::parse)?;
+    let current = file.get_list_forced(name)?;
+
+    if let Some(new_name) =
+        new_name.as_ref().filter(|name| **name != current.name)
+    {
+        file.list_exists_forced(new_name)?;
+    }
+
+    let list = file.get_list_mut_forced(name)?;
+
+    if let Some(new_name) = new_name
+    {
+        list.name = new_name;
+    }
+
+    if let Some(kind) = kind
+    {
+        list.kind = kind;
+    }
+
+    tos::send_success(&format!(
+        "List {} updated{}.",
+        tos::format_list_name(&list.name),
+        match &list.kind
+        {
+            Some(kind) => format!(" ({})", tos::format_list_kind(kind)),
+            None => String::new(),
+        }
+    ));
+
+    Ok(())
+}
+
+/// Move the items of someday lists whose tickler date has come to the inbox
+pub fn resurface_tickled_items(file: &mut File) -> EResult<()>
+{
+    let count = file.resurface_tickled(dates::today());
 
-    let list = file.get_list_mut_forced(list_name)?;
+    if count > 0
+    {
+        tos::send_info(&format!(
+            "{} someday items resurfaced in the inbox.",
+            tos::format_number(count)
+        ));
+    }
 
-    list.project_exists_forced(&name)?;
+    Ok(())
+}
 
-    let project = Project::new(name);
+pub fn remove_list(file: &mut File, name: &str) -> EResult<()>
+{
+    let index =
+        match file.lists.iter().position(|list: &List| list.name == name)
+        {
+            Some(index) => index,
+            None =>
+            {
+                return Err(Box::new(io::Error::new(
+                    ErrorKind::NotFound,
+                    "List not found.",
+                )));
+            }
+        };
 
-    let project_name = project.name.clone();
+    if !prompt::confirm(&format!(
+        "Remove list {} and everything in it?",
+        tos::format_list_name(name)
+    ))?
+    {
+        tos::send_info("Nothing was removed.");
 
-    let project_status = project.status();
+        return Ok(());
+    }
 
-    list.push_project(project);
+    file.lists.remove(index);
 
     tos::send_success(&format!(
-        "Project {}/{} ({}) created.",
-        tos::format_list_name(&list_name),
-        (list.projects().len() - 1)
-            .to_string()
-            .color(tos::COLOR_NUM_VALUE),
-        tos::format_project(&project_name, &project_status),
+        "List {} removed.",
+        tos::format_list_name(&name)
     ));
 
     Ok(())
 }
 
-pub fn remove_project(file: &mut File, path: &str) -> EResult<()>
+/// Pair items with their index, ordered as requested by `sort`
+fn order_items<T>(
+    items: &[T],
+    sort: SortOrder,
+    priority: impl Fn(&T) -> Option<Priority>,
+) -> Vec<(usize, &T)>
 {
-    let project_path = itempath::ContainerPath::parse(path)?;
+    let mut ordered: Vec<(usize, &T)> = items.iter().enumerate().collect();
+
+    if let SortOrder::Priority = sort
+    {
+        ordered.sort_by_key(|(_, item)| Priority::sort_key(priority(item)));
+    }
+
+    ordered
+}
 
-    let list = file.get_list_mut_forced(&project_path.list_name)?;
+/// Build a node for each of the `tasks`, holding their checklist and, if
+/// `verbose` is set, their description, leaving out the ones without the
+/// `tag` if one is given
+fn task_nodes(
+    tasks_by_id: &gtd::TaskIndex,
+    tasks: &[Task],
+    sort: SortOrder,
+    tag: Option<&str>,
+    verbose: bool,
+) -> Vec<tos::TreeNode>
+{
+    let mut nodes: Vec<tos::TreeNode> = vec![];
 
-    if let Some(index) = project_path.project_index
+    for (index, task) in order_items(tasks, sort, |t| t.priority)
     {
-        let project = list.get_project_forced(index)?;
-        let project_name = project.name.clone();
-        let project_status = project.status();
+        if tag.is_some_and(|tag| !task.has_tag(tag))
+        {
+            continue;
+        }
+
+        let mut node = tos::TreeNode::new(format!(
+            "{}. {}{}",
+            // TODO: Use this function at the formatting func
+            indexer::index_to_identifier(index).color(tos::palette().number),
+            tos::format_task(task, &tasks_by_id.open_blockers(task)),
+            if task.checklist.is_empty()
+            {
+                String::new()
+            }
+            else
+            {
+                format!(
+                    " {}",
+                    tos::format_progress(
+                        task.checklist_completed(),
+                        task.checklist.len()
+                    )
+                )
+            }
+        ));
 
-        list.remove_project(index);
+        if verbose
+        {
+            node.description = task.description.clone();
+        }
 
-        tos::send_success(&format!(
-            "Project {}/{} ({}) removed.",
-            tos::format_list_name(&project_path.list_name),
-            index.to_string().color(tos::COLOR_NUM_VALUE),
-            tos::format_project(&project_name, &project_status),
+        for (item_index, item) in task.checklist.iter().enumerate()
+        {
+            node.children.push(tos::TreeNode::new(format!(
+                "{}. {}",
+                tos::format_index(item_index),
+                tos::format_checklist_item(item)
+            )));
+        }
+
+        nodes.push(node);
+    }
+
+    nodes
+}
+
+/// Build a node for each of the `projects`, holding their sub-projects and
+/// tasks if `all` is set, with the descriptions of the latter if `verbose`
+/// is set
+///
+/// If a `tag` is given, only the projects that have it or that hold items
+/// with it are shown, and only those items are shown within the latter.
+fn project_nodes(
+    tasks_by_id: &gtd::TaskIndex,
+    projects: &[Project],
+    sort: SortOrder,
+    all: bool,
+    tag: Option<&str>,
+    verbose: bool,
+) -> Vec<tos::TreeNode>
+{
+    let mut nodes: Vec<tos::TreeNode> = vec![];
+
+    for (index, project) in order_items(projects, sort, |p| p.priority)
+    {
+        if tag.is_some_and(|tag| {
+            !project.has_tag(tag) && !project.contains_tag(tag)
+        })
+        {
+            continue;
+        }
+
+        let tag = tag.filter(|tag| !project.has_tag(tag));
+
+        let mut node = tos::TreeNode::new(format!(
+            "{}. {} {}",
+            tos::format_index(index),
+            tos::format_project(project),
+            if project.total_tasks() == 0
+            {
+                "(No tasks)".to_string()
+            }
+            else
+            {
+                tos::format_progress(
+                    project.total_tasks_completed(),
+                    project.total_tasks(),
+                )
+            },
         ));
+
+        if all && project.total_tasks() > 0
+        {
+            node.children = project_nodes(
+                tasks_by_id,
+                project.projects(),
+                sort,
+                all,
+                tag,
+                verbose,
+            );
+            node.children.extend(task_nodes(
+                tasks_by_id,
+                project.tasks(),
+                sort,
+                tag,
+                verbose,
+            ));
+        }
+
+        nodes.push(node);
     }
 
-    Ok(())
+    nodes
 }
 
-pub fn show_all_lists(file: &mut File) -> EResult<()>
+pub fn show_list(
+    file: &mut File,
+    name: &str,
+    all: bool,
+    sort: SortOrder,
+    tag: Option<String>,
+    verbose: bool,
+) -> EResult<()>
 {
-    if file.lists().is_empty()
+    let name = name.to_lowercase();
+
+    let list = file.get_list_forced(&name)?;
+
+    let formatted_name = tos::format_list_name(&name);
+
+    if list.tasks().is_empty() && list.projects().is_empty()
     {
-        tos::send_info(&format!("There are no lists in the workspace."));
+        tos::send_info(&format!("List {formatted_name} is empty."));
+
         return Ok(());
     }
 
+    let tag = tag.as_deref().map(gtd::parse_tag);
+
+    if let Some(tag) = &tag
+    {
+        if !list.has_tag(tag) && !list.contains_tag(tag)
+        {
+            tos::send_info(&format!(
+                "List {} has no items tagged {}.",
+                formatted_name,
+                tos::format_tag(tag)
+            ));
+
+            return Ok(());
+        }
+    }
+
+    // Everything within a tagged list counts as tagged
+    let tag = tag.filter(|tag| !list.has_tag(tag));
+    let tag = tag.as_deref();
+
+    let tasks_by_id = file.task_index();
     let mut output = tos::OutputBlock::new();
 
     output
-        .insert_line("Lists in the workspace", 0)
+        .insert_line(
+            &format!(
+                "Contents of list {}{}",
+                formatted_name.color(tos::palette().identifier),
+                list.tags
+                    .iter()
+                    .map(|tag| format!(" {}", tos::format_tag(tag)))
+                    .collect::<String>(),
+            ),
+            0,
+        )
         .insert_text("\n");
 
-    for list in file.lists.iter()
+    // TODO: Show the empty projects first
+    if !list.projects().is_empty()
+        && tag.is_none_or(|tag| {
+            list.projects()
+                .iter()
+                .any(|p| p.has_tag(tag) || p.contains_tag(tag))
+        })
     {
         output.insert_line(
             &format!(
-                "• {} ({} tasks, {} projects)",
-                tos::format_list_name(&list.name),
-                tos::format_number(list.tasks().len()),
-                tos::format_number(list.projects().len())
+                "{} {}",
+                tos::format_section_name("projects"),
+                tos::format_progress(
+                    list.projects_completed(),
+                    list.projects().len()
+                ),
             ),
             1,
         );
-    }
 
-    output.send();
+        let nodes = project_nodes(
+            &tasks_by_id,
+            list.projects(),
+            sort,
+            all,
+            tag,
+            verbose,
+        );
+
+        for (position, node) in nodes.iter().enumerate()
+        {
+            output.insert_tree(node, 2);
+
+            if !node.children.is_empty() && position < nodes.len() - 1
+            {
+                output.insert_text("\n");
+            }
+        }
+
+        output.insert_text("\n");
+    }
+
+    if !list.tasks().is_empty()
+        && tag.is_none_or(|tag| list.tasks().iter().any(|t| t.has_tag(tag)))
+    {
+        output.insert_line(
+            &format!(
+                "{} {}",
+                tos::format_section_name("tasks"),
+                tos::format_progress(
+                    list.tasks_completed(),
+                    list.tasks().len()
+                ),
+            ),
+            1,
+        );
+
+        for node in task_nodes(&tasks_by_id, list.tasks(), sort, tag, verbose)
+        {
+            output.insert_tree(&node, 2);
+        }
+    }
+
+    output.send();
+
+    Ok(())
+}
+
+pub fn show_project(
+    file: &mut File,
+    path: &str,
+    sort: SortOrder,
+    tag: Option<String>,
+    verbose: bool,
+) -> EResult<()>
+{
+    let project_path = itempath::ContainerPath::parse_project(path)?;
+
+    let project = file.get_project(&project_path)?;
+
+    let has_details = project.outcome.is_some()
+        || project.notes.is_some()
+        || !project.references.is_empty();
+
+    if project.tasks().is_empty()
+        && project.projects().is_empty()
+        && !has_details
+    {
+        tos::send_info(&format!(
+            "Project {} is empty.",
+            tos::format_project_name(&project.name, &project.status())
+        ));
+
+        return Ok(());
+    }
+
+    let tag = tag.as_deref().map(gtd::parse_tag);
+
+    if let Some(tag) = &tag
+    {
+        if !project.has_tag(tag) && !project.contains_tag(tag)
+        {
+            tos::send_info(&format!(
+                "Project {} has no items tagged {}.",
+                tos::format_project_name(&project.name, &project.status()),
+                tos::format_tag(tag)
+            ));
+
+            return Ok(());
+        }
+    }
+
+    // Everything within a tagged project counts as tagged
+    let tag = tag.filter(|tag| !project.has_tag(tag));
+    let tag = tag.as_deref();
+
+    let tasks_by_id = file.task_index();
+    let mut output = tos::OutputBlock::new();
+
+    output
+        .insert_line(
+            &format!("Contents of project {}", tos::format_project(project)),
+            0,
+        )
+        .insert_text("\n");
+
+    if let Some(outcome) = &project.outcome
+    {
+        output.insert_line(
+            &format!("{} {}", tos::format_section_name("outcome:"), outcome),
+            0,
+        );
+    }
+
+    if let Some(notes) = &project.notes
+    {
+        output.insert_line(&tos::format_section_name("notes"), 0);
+
+        for line in notes.lines()
+        {
+            output.insert_line(line, 1);
+        }
+    }
+
+    if !project.references.is_empty()
+    {
+        output.insert_line(&tos::format_section_name("references"), 0);
+
+        for (index, reference) in project.references.iter().enumerate()
+        {
+            output.insert_line(
+                &format!("{}. {}", tos::format_index(index), reference),
+                1,
+            );
+        }
+    }
+
+    if has_details
+    {
+        output.insert_text("\n");
+    }
+
+    for node in project_nodes(
+        &tasks_by_id,
+        project.projects(),
+        sort,
+        true,
+        tag,
+        verbose,
+    )
+    .into_iter()
+    .chain(task_nodes(
+        &tasks_by_id,
+        project.tasks(),
+        sort,
+        tag,
+        verbose,
+    ))
+    {
+        output.insert_tree(&node, 0);
+    }
+
+    output.send();
+
+    Ok(())
+}
+
+pub fn create_project(
+    file: &mut File,
+    path: &str,
+    mut project: Project,
+) -> EResult<()>
+{
+    project.name = project.name.to_lowercase();
+    project.area = project.area.map(|area| area.to_lowercase());
+    project.goal = project.goal.map(|goal| goal.to_lowercase());
+
+    if let Some(area) = &project.area
+    {
+        file.get_area(area)?;
+    }
+
+    if let Some(goal) = &project.goal
+    {
+        file.get_goal(goal)?;
+    }
+
+    let container_path = itempath::ContainerPath::parse(path)?;
+
+    let container = file.get_project_container_mut(&container_path)?;
+
+    container.project_exists_forced(&project.name)?;
+
+    let formatted_project = tos::format_project(&project);
+
+    container.push_project(project);
+
+    tos::send_success(&format!(
+        "Project {} ({}) created.",
+        container_path
+            .child(container.projects().len() - 1)
+            .tos_format(),
+        formatted_project,
+    ));
+
+    Ok(())
+}
+
+pub fn edit_project(file: &mut File, args: &ProjectEditArgs) -> EResult<()>
+{
+    let project_path = itempath::ContainerPath::parse_project(&args.path)?;
+
+    let priority = parse_clearable(&args.priority, Priority::parse)?;
+    let area = parse_clearable(&args.area, |area| {
+        Ok(file.get_area(&area.to_lowercase())?.name.clone())
+    })?;
+    let goal = parse_clearable(&args.goal, |goal| {
+        Ok(file.get_goal(&goal.to_lowercase())?.name.clone())
+    })?;
+    let outcome = parse_clearable(&args.outcome, |o| Ok(o.into()))?;
+    let tickler = parse_clearable(&args.tickler, dates::parse_date)?;
+    let mut notes = parse_clearable(&args.notes, |n| Ok(n.into()))?;
+
+    let mut removed_references = args
+        .remove_references
+        .iter()
+        .map(|index| indexer::identifier_to_index(index))
+        .collect::<EResult<Vec<usize>>>()?;
+
+    // Remove the references from last to first so that indexes stay valid
+    removed_references.sort_unstable_by(|a, b| b.cmp(a));
+    removed_references.dedup();
+
+    let current = file.get_project(&project_path)?;
+
+    let name = args.name.as_ref().map(|name| name.to_lowercase());
+
+    if let (Some(name), Some((parent_path, _))) = (
+        name.as_ref().filter(|name| **name != current.name),
+        project_path.split_last(),
+    )
+    {
+        file.get_project_container(&parent_path)?
+            .project_exists_forced(name)?;
+    }
+
+    let project = file.get_project_mut(&project_path)?;
+
+    if let Some(&index) = removed_references.first()
+    {
+        if index >= project.references.len()
+        {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::NotFound,
+                "Reference not found.",
+            )));
+        }
+    }
+
+    if args.editor
+    {
+        let edited =
+            editor::edit_text(project.notes.as_deref().unwrap_or_default())?;
+
+        notes = Some(
+            if edited.is_empty()
+            {
+                None
+            }
+            else
+            {
+                Some(edited)
+            },
+        );
+    }
+
+    if let Some(name) = name
+    {
+        project.name = name;
+    }
+
+    if let Some(priority) = priority
+    {
+        project.priority = priority;
+    }
+
+    if args.sequential || args.parallel
+    {
+        project.sequential = args.sequential;
+    }
+
+    if let Some(area) = area
+    {
+        project.area = area;
+    }
+
+    if let Some(goal) = goal
+    {
+        project.goal = goal;
+    }
+
+    if let Some(outcome) = outcome
+    {
+        project.outcome = outcome;
+    }
+
+    if let Some(tickler) = tickler
+    {
+        project.tickler = tickler;
+    }
+
+    if let Some(notes) = notes
+    {
+        project.notes = notes;
+    }
+
+    for index in removed_references
+    {
+        project.references.remove(index);
+    }
+
+    project
+        .references
+        .extend(args.add_references.iter().cloned());
+
+    tos::send_success(&format!(
+        "Project {} updated ({}).",
+        &project_path.tos_format(),
+        tos::format_project(project),
+    ));
+
+    Ok(())
+}
+
+pub fn remove_project(file: &mut File, path: &str) -> EResult<()>
+{
+    let project_path = itempath::ContainerPath::parse_project(path)?;
+
+    let formatted_project =
+        tos::format_project(file.get_project(&project_path)?);
+
+    if !prompt::confirm(&format!(
+        "Remove project {} and everything in it?",
+        project_path.tos_format()
+    ))?
+    {
+        tos::send_info("Nothing was removed.");
+
+        return Ok(());
+    }
+
+    if let Some((parent_path, index)) = project_path.split_last()
+    {
+        file.get_project_container_mut(&parent_path)?
+            .remove_project(index);
+    }
+
+    tos::send_success(&format!(
+        "Project {} ({}) removed.",
+        project_path.tos_format(),
+        formatted_project,
+    ));
+
+    Ok(())
+}
+
+/// The order of next actions: by priority, then by due date with the undated
+/// ones last
+fn action_order(task: &Task) -> impl Ord
+{
+    (
+        Priority::sort_key(task.priority),
+        task.due.is_none(),
+        task.due,
+    )
+}
+
+/// Build a node for each of the `projects` with available actions, holding
+/// those actions and their sub-projects, and one for each stalled project
+fn next_action_nodes(
+    projects: &[Project],
+    parent_path: &itempath::ContainerPath,
+    fits: &dyn Fn(&Task) -> bool,
+    counts: &mut (usize, usize),
+) -> Vec<tos::TreeNode>
+{
+    let mut nodes: Vec<tos::TreeNode> = vec![];
+
+    for (index, project) in
+        order_items(projects, SortOrder::Priority, |p| p.priority)
+    {
+        let project_path = parent_path.child(index);
+
+        let formatted_project = format!(
+            "{} {}",
+            project_path.tos_format(),
+            tos::format_project(project)
+        );
+
+        if project.stalled()
+        {
+            nodes.push(tos::TreeNode::new(format!(
+                "{} {}",
+                formatted_project,
+                tos::format_stalled()
+            )));
+            counts.1 += 1;
+            continue;
+        }
+
+        let mut actions: Vec<(usize, &Task)> = project
+            .available_tasks()
+            .into_iter()
+            .filter(|(_, task)| fits(task))
+            .collect();
+
+        actions.sort_by_key(|(_, task)| action_order(task));
+
+        let mut node = tos::TreeNode::new(formatted_project);
+
+        for (task_index, task) in actions
+        {
+            node.children.push(tos::TreeNode::new(format!(
+                "{} {}",
+                project_path.task(task_index).tos_format(),
+                tos::format_task(task, &[])
+            )));
+            counts.0 += 1;
+        }
+
+        node.children.extend(next_action_nodes(
+            project.projects(),
+            &project_path,
+            fits,
+            counts,
+        ));
+
+        if !node.children.is_empty()
+        {
+            nodes.push(node);
+        }
+    }
+
+    nodes
+}
+
+pub fn show_next_actions(
+    file: &mut File,
+    time: Option<u32>,
+    energy: Option<Energy>,
+    context: Option<String>,
+) -> EResult<()>
+{
+    let context = context.as_deref().map(gtd::parse_context);
+    let tasks_by_id = file.task_index();
+
+    let fits = |task: &Task| {
+        !task.done()
+            && task.fits(time, energy, context.as_deref())
+            && tasks_by_id.open_blockers(task).is_empty()
+    };
+
+    let mut output = tos::OutputBlock::new();
+    // The amount of available actions and of stalled projects
+    let mut counts = (0, 0);
+
+    for list in file.lists()
+    {
+        if !list.is_actionable()
+        {
+            continue;
+        }
+
+        let list_path = itempath::ContainerPath {
+            list_name: list.name.clone(),
+            project_indexes: vec![],
+        };
+
+        let mut node = tos::TreeNode::new(tos::format_list_name(&list.name));
+
+        node.children =
+            next_action_nodes(list.projects(), &list_path, &fits, &mut counts);
+
+        let mut actions: Vec<(usize, &Task)> = list
+            .tasks()
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| fits(task))
+            .collect();
+
+        actions.sort_by_key(|(_, task)| action_order(task));
+
+        for (index, task) in actions
+        {
+            node.children.push(tos::TreeNode::new(format!(
+                "{} {}",
+                list_path.task(index).tos_format(),
+                tos::format_task(task, &[])
+            )));
+            counts.0 += 1;
+        }
+
+        if node.children.is_empty()
+        {
+            continue;
+        }
+
+        output.insert_tree(&node, 1);
+
+        output.insert_text("\n");
+    }
+
+    let (action_count, stalled_count) = counts;
+
+    if action_count == 0 && stalled_count == 0
+    {
+        tos::send_info("No tasks fit the available time, energy and context.");
+
+        return Ok(());
+    }
+
+    let mut header = tos::OutputBlock::new();
+
+    header.insert_line(
+        &format!(
+            "Next actions {}{}",
+            tos::format_number(action_count),
+            if stalled_count > 0
+            {
+                format!(
+                    " ({} stalled projects)",
+                    tos::format_number(stalled_count)
+                )
+            }
+            else
+            {
+                String::new()
+            }
+        ),
+        0,
+    );
+
+    header.insert_text("\n").insert_text(output.text());
+
+    header.send();
+
+    Ok(())
+}
+
+pub fn show_all_lists(file: &mut File) -> EResult<()>
+{
+    if file.lists().is_empty()
+    {
+        tos::send_info(&format!("There are no lists in the workspace."));
+        return Ok(());
+    }
+
+    let mut output = tos::OutputBlock::new();
+
+    output
+        .insert_line("Lists in the workspace", 0)
+        .insert_text("\n");
+
+    let mut table = tos::Table::new(&[
+        tos::Align::Left,
+        tos::Align::Left,
+        tos::Align::Right,
+        tos::Align::Right,
+        tos::Align::Left,
+    ]);
+
+    table.add_row(
+        ["list", "kind", "tasks", "projects", "tags"]
+            .map(tos::format_section_name)
+            .to_vec(),
+    );
+
+    for list in file.lists.iter()
+    {
+        table.add_row(vec![
+            tos::format_list_name(&list.name),
+            list.kind
+                .as_ref()
+                .map(tos::format_list_kind)
+                .unwrap_or_default(),
+            tos::format_number(list.tasks().len()),
+            tos::format_number(list.projects().len()),
+            list.tags
+                .iter()
+                .map(|tag| tos::format_tag(tag))
+                .collect::<Vec<String>>()
+                .join(" "),
+        ]);
+    }
+
+    output.insert_table(&table, 1);
+
+    output.send();
+
+    Ok(())
+}
+
+/// Get the item of the given `kind` at `path`, so that its tags can be changed
+fn get_tagged_mut<'a>(
+    file: &'a mut File,
+    path: &str,
+    kind: ItemKind,
+) -> EResult<(String, &'a mut dyn Tagged)>
+{
+    Ok(match kind
+    {
+        ItemKind::Task =>
+        {
+            let task_path = itempath::TaskPath::parse(path)?;
+            (task_path.tos_format(), file.get_task_mut(&task_path)?)
+        }
+        ItemKind::Project =>
+        {
+            let project_path = itempath::ContainerPath::parse_project(path)?;
+            (
+                project_path.tos_format(),
+                file.get_project_mut(&project_path)?,
+            )
+        }
+        ItemKind::List =>
+        {
+            let name = path.to_lowercase();
+            (
+                tos::format_list_name(&name),
+                file.get_list_mut_forced(&name)?,
+            )
+        }
+    })
+}
+
+pub fn tag_items(
+    file: &mut File,
+    tag: &str,
+    paths: &[String],
+    kind: ItemKind,
+    add: bool,
+) -> EResult<()>
+{
+    let tag = gtd::parse_tag(tag);
+
+    let mut changed: Vec<String> = vec![];
+
+    for path in paths
+    {
+        let (formatted_path, item) = get_tagged_mut(file, path, kind)?;
+
+        let was_changed = if add
+        {
+            item.add_tag(&tag)
+        }
+        else
+        {
+            item.remove_tag(&tag)
+        };
+
+        if was_changed
+        {
+            changed.push(formatted_path);
+        }
+    }
+
+    if changed.is_empty()
+    {
+        tos::send_info(&format!(
+            "No item was {} {}.",
+            if add { "missing the tag" } else { "tagged" },
+            tos::format_tag(&tag)
+        ));
+
+        return Ok(());
+    }
+
+    tos::send_success(&format!(
+        "Tag {} {} {}.",
+        tos::format_tag(&tag),
+        if add { "added to" } else { "removed from" },
+        changed.join(", "),
+    ));
+
+    Ok(())
+}
+
+pub fn rename_tag(file: &mut File, tag: &str, new_tag: &str) -> EResult<()>
+{
+    let tag = gtd::parse_tag(tag);
+    let new_tag = gtd::parse_tag(new_tag);
+
+    let count = file.rename_tag(&tag, &new_tag);
+
+    if count == 0
+    {
+        return Err(Box::new(io::Error::new(
+            ErrorKind::NotFound,
+            format!("No item is tagged {}.", tag),
+        )));
+    }
+
+    tos::send_success(&format!(
+        "Tag {} renamed to {} on {} items.",
+        tos::format_tag(&tag),
+        tos::format_tag(&new_tag),
+        tos::format_number(count),
+    ));
+
+    Ok(())
+}
+
+pub fn show_tags(file: &mut File) -> EResult<()>
+{
+    // The amount of tasks, projects and lists with each tag
+    let mut counts: BTreeMap<&str, (usize, usize, usize)> = BTreeMap::new();
+
+    for (_, task) in file.all_tasks()
+    {
+        for tag in &task.tags
+        {
+            counts.entry(tag).or_default().0 += 1;
+        }
+    }
+
+    for (_, project) in file.all_projects()
+    {
+        for tag in &project.tags
+        {
+            counts.entry(tag).or_default().1 += 1;
+        }
+    }
+
+    for list in file.lists()
+    {
+        for tag in &list.tags
+        {
+            counts.entry(tag).or_default().2 += 1;
+        }
+    }
+
+    if counts.is_empty()
+    {
+        tos::send_info("There are no tags in the workspace.");
+
+        return Ok(());
+    }
+
+    let mut output = tos::OutputBlock::new();
+
+    output
+        .insert_line("Tags in the workspace", 0)
+        .insert_text("\n");
+
+    let mut table = tos::Table::new(&[
+        tos::Align::Left,
+        tos::Align::Right,
+        tos::Align::Right,
+        tos::Align::Right,
+    ]);
+
+    table.add_row(
+        ["tag", "tasks", "projects", "lists"]
+            .map(tos::format_section_name)
+            .to_vec(),
+    );
+
+    for (tag, (tasks, projects, lists)) in counts
+    {
+        table.add_row(vec![
+            tos::format_tag(tag),
+            tos::format_number(tasks),
+            tos::format_number(projects),
+            tos::format_number(lists),
+        ]);
+    }
+
+    output.insert_table(&table, 1);
+
+    output.send();
+
+    Ok(())
+}
+
+pub fn create_area(
+    file: &mut File,
+    name: &str,
+    description: Option<String>,
+) -> EResult<()>
+{
+    let name = name.to_lowercase();
+
+    if file.get_area(&name).is_ok()
+    {
+        return Err(Box::new(io::Error::new(
+            ErrorKind::AlreadyExists,
+            "Area already exists.",
+        )));
+    }
+
+    file.areas.push(Area {
+        name: name.clone(),
+        description,
+    });
+
+    tos::send_success(&format!(
+        "Area {} created.",
+        tos::format_area_name(&name)
+    ));
+
+    Ok(())
+}
+
+pub fn remove_area(file: &mut File, name: &str) -> EResult<()>
+{
+    let name = name.to_lowercase();
+
+    file.get_area(&name)?;
+
+    file.areas.retain(|area| area.name != name);
+
+    for goal in file.goals.iter_mut()
+    {
+        if goal.area.as_ref() == Some(&name)
+        {
+            goal.area = None;
+        }
+    }
+
+    file.for_each_project_mut(&mut |project| {
+        if project.area.as_ref() == Some(&name)
+        {
+            project.area = None;
+        }
+    });
+
+    tos::send_success(&format!(
+        "Area {} removed.",
+        tos::format_area_name(&name)
+    ));
+
+    Ok(())
+}
+
+pub fn create_goal(
+    file: &mut File,
+    name: &str,
+    area: Option<String>,
+    due: Option<NaiveDate>,
+) -> EResult<()>
+{
+    let name = name.to_lowercase();
+    let area = area.map(|area| area.to_lowercase());
+
+    if file.get_goal(&name).is_ok()
+    {
+        return Err(Box::new(io::Error::new(
+            ErrorKind::AlreadyExists,
+            "Goal already exists.",
+        )));
+    }
+
+    if let Some(area) = &area
+    {
+        file.get_area(area)?;
+    }
+
+    let goal = Goal { name, area, due };
+
+    let formatted_goal = tos::format_goal(&goal);
+
+    file.goals.push(goal);
+
+    tos::send_success(&format!("{} created.", formatted_goal));
+
+    Ok(())
+}
+
+pub fn remove_goal(file: &mut File, name: &str) -> EResult<()>
+{
+    let name = name.to_lowercase();
+
+    file.get_goal(&name)?;
+
+    file.goals.retain(|goal| goal.name != name);
+
+    file.for_each_project_mut(&mut |project| {
+        if project.goal.as_ref() == Some(&name)
+        {
+            project.goal = None;
+        }
+    });
+
+    tos::send_success(&format!(
+        "Goal {} removed.",
+        tos::format_goal_name(&name)
+    ));
+
+    Ok(())
+}
+
+/// Insert a line for each of the active `projects` of an area or goal
+fn insert_area_projects(
+    output: &mut tos::OutputBlock,
+    projects: &[(itempath::ContainerPath, &Project)],
+    padding_level: usize,
+)
+{
+    for (path, project) in projects
+    {
+        output.insert_line(
+            &format!("{} {}", path.tos_format(), tos::format_project(project)),
+            padding_level,
+        );
+    }
+}
+
+pub fn show_areas(file: &mut File) -> EResult<()>
+{
+    if file.areas.is_empty() && file.goals.is_empty()
+    {
+        tos::send_info(
+            "There are no areas of focus or goals in the workspace.",
+        );
+
+        return Ok(());
+    }
+
+    let mut output = tos::OutputBlock::new();
+
+    let neglected_count = file.neglected_areas().len();
+
+    output
+        .insert_line(
+            &format!(
+                "Areas of focus and goals{}",
+                if neglected_count > 0
+                {
+                    format!(
+                        " ({} areas without active projects)",
+                        tos::format_number(neglected_count)
+                    )
+                }
+                else
+                {
+                    String::new()
+                }
+            ),
+            0,
+        )
+        .insert_text("\n");
+
+    let area_names: Vec<Option<&str>> = file
+        .areas
+        .iter()
+        .map(|area| Some(area.name.as_str()))
+        .chain([None])
+        .collect();
+
+    for area_name in area_names
+    {
+        let goals: Vec<&Goal> = file
+            .goals
+            .iter()
+            .filter(|goal| goal.area.as_deref() == area_name)
+            .collect();
+
+        // Projects linked to one of the area's goals are shown under it
+        let projects: Vec<(itempath::ContainerPath, &Project)> =
+            match area_name
+            {
+                Some(area_name) => file.active_area_projects(area_name),
+                None => vec![],
+            }
+            .into_iter()
+            .filter(|(_, project)| {
+                !goals
+                    .iter()
+                    .any(|goal| project.goal.as_ref() == Some(&goal.name))
+            })
+            .collect();
+
+        match area_name
+        {
+            Some(area_name) =>
+            {
+                let area = file.get_area(area_name)?;
+
+                output.insert_line(
+                    &format!(
+                        "{}{}",
+                        tos::format_area_name(&area.name),
+                        if file.active_area_projects(area_name).is_empty()
+                        {
+                            format!(" {}", tos::format_no_active_projects())
+                        }
+                        else
+                        {
+                            String::new()
+                        }
+                    ),
+                    1,
+                );
+
+                if let Some(description) = &area.description
+                {
+                    output.insert_line(description, 2);
+                }
+            }
+            None if goals.is_empty() => continue,
+            None =>
+            {
+                output
+                    .insert_line(&tos::format_section_name("other goals"), 1);
+            }
+        }
+
+        for goal in goals
+        {
+            let goal_projects: Vec<(itempath::ContainerPath, &Project)> = file
+                .active_projects()
+                .into_iter()
+                .filter(|(_, project)| {
+                    project.goal.as_ref() == Some(&goal.name)
+                })
+                .collect();
+
+            output.insert_line(
+                &format!(
+                    "{}{}",
+                    tos::format_goal(goal),
+                    if goal_projects.is_empty()
+                    {
+                        format!(" {}", tos::format_no_active_projects())
+                    }
+                    else
+                    {
+                        String::new()
+                    }
+                ),
+                2,
+            );
+
+            insert_area_projects(&mut output, &goal_projects, 3);
+        }
+
+        insert_area_projects(&mut output, &projects, 2);
+
+        output.insert_text("\n");
+    }
+
+    output.send();
+
+    Ok(())
+}
+
+pub fn show_waiting_for(file: &mut File) -> EResult<()>
+{
+    let today = dates::today();
+
+    let waiting_lists: Vec<&str> = file
+        .lists()
+        .iter()
+        .filter(|list| list.kind == Some(ListKind::WaitingFor))
+        .map(|list| list.name.as_str())
+        .collect();
+
+    // The tasks waited on, grouped by the person they were handed over to
+    let mut people: BTreeMap<String, Vec<(itempath::TaskPath, &Task)>> =
+        BTreeMap::new();
+
+    for (path, task) in file.all_tasks()
+    {
+        if task.done()
+        {
+            continue;
+        }
+
+        let person = match &task.delegate
+        {
+            Some(person) => person.clone(),
+            None if waiting_lists.contains(&path.list_name.as_str()) =>
+            {
+                "Nobody in particular".to_owned()
+            }
+            None => continue,
+        };
+
+        people.entry(person).or_default().push((path, task));
+    }
+
+    if people.is_empty()
+    {
+        tos::send_info("You are not waiting on anything.");
+
+        return Ok(());
+    }
+
+    let mut output = tos::OutputBlock::new();
+
+    let task_count: usize = people.values().map(Vec::len).sum();
+
+    let overdue_count = people
+        .values()
+        .flatten()
+        .filter(|(_, task)| task.follow_up.is_some_and(|date| date < today))
+        .count();
+
+    output
+        .insert_line(
+            &format!(
+                "Waiting for {}{}",
+                tos::format_number(task_count),
+                if overdue_count > 0
+                {
+                    format!(
+                        " ({} overdue follow-ups)",
+                        tos::format_number(overdue_count)
+                    )
+                }
+                else
+                {
+                    String::new()
+                }
+            ),
+            0,
+        )
+        .insert_text("\n");
+
+    for (person, mut tasks) in people
+    {
+        tasks
+            .sort_by_key(|(_, task)| task.follow_up.unwrap_or(NaiveDate::MAX));
+
+        let mut node = tos::TreeNode::new(tos::format_delegate(&person));
+
+        for (path, task) in tasks
+        {
+            let mut line = format!(
+                "{} {}",
+                path.tos_format(),
+                tos::format_task(task, &[])
+            );
+
+            if let Some(delegated) = &task.delegated
+            {
+                line.push_str(&format!(
+                    " (since {})",
+                    dates::format_date(delegated).color(tos::palette().date)
+                ));
+            }
+
+            if let Some(follow_up) = &task.follow_up
+            {
+                line.push_str(&format!(
+                    " (follow up {})",
+                    tos::format_follow_up(follow_up, today)
+                ));
+            }
+
+            node.children.push(tos::TreeNode::new(line));
+        }
+
+        output.insert_tree(&node, 1).insert_text("\n");
+    }
+
+    output.send();
+
+    Ok(())
+}
+
+/// Capture each of the `items` as a task, in the project named by its
+/// shorthand or at the end of the `list`
+pub fn capture(
+    file: &mut File,
+    items: &[String],
+    list: Option<String>,
+    raw: bool,
+) -> EResult<()>
+{
+    if items.is_empty()
+    {
+        tos::send_info("Nothing to capture.");
+
+        return Ok(());
+    }
+
+    let list_name = match list
+    {
+        Some(name) => name.to_lowercase(),
+        None =>
+        {
+            match config::get()
+                .capture_list
+                .clone()
+                .or_else(|| file.inbox_name())
+            {
+                Some(name) => name,
+                None =>
+                {
+                    return Err(Box::new(io::Error::new(
+                        ErrorKind::NotFound,
+                        "There is no inbox list in the workspace.",
+                    )));
+                }
+            }
+        }
+    };
+
+    file.get_list_forced(&list_name)?;
+
+    // Parse every item first, so that a mistake doesn't leave only some of
+    // them captured
+    let mut captures: Vec<capture::Capture> = vec![];
+
+    for item in items
+    {
+        let capture = if raw
+        {
+            capture::Capture {
+                task: Task::new(item.trim().to_owned(), None),
+                project: None,
+            }
+        }
+        else
+        {
+            capture::parse_shorthand(item)?
+        };
+
+        if capture.task.name.is_empty()
+        {
+            return Err(Box::new(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("No task name found in \"{}\".", item),
+            )));
+        }
+
+        captures.push(capture);
+    }
+
+    for capture in captures
+    {
+        let path = match &capture.project
+        {
+            Some(project_name) =>
+            {
+                match file
+                    .all_projects()
+                    .into_iter()
+                    .find(|(_, project)| &project.name == project_name)
+                {
+                    Some((path, _)) => path.to_string(),
+                    None =>
+                    {
+                        tos::send_info(&format!(
+                            "No project is named \"{}\"; capturing in {} \
+                             instead.",
+                            project_name,
+                            tos::format_list_name(&list_name)
+                        ));
+
+                        list_name.clone()
+                    }
+                }
+            }
+            None => list_name.clone(),
+        };
+
+        create_task(file, path, capture.task)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,189 @@
+use std::io;
+use std::path::Path;
+
+use clap_complete::env::Shells;
+use clap_complete::CompletionCandidate;
+use clap_complete::Shell;
+
+use crate::config;
+use crate::dirs;
+use crate::file;
+use crate::gtd::File;
+use crate::gtd::ListContainer;
+use crate::EResult;
+
+/// The environment variable that makes `vgtd` print completions instead of
+/// running a subcommand
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Load the workspace the completions are for: the one in the current
+/// directory, or else the global one
+fn load_workspace() -> Option<File>
+{
+    let path = match Path::new(dirs::GTD_FILE_PATH).exists()
+    {
+        true => dirs::GTD_FILE_PATH.to_owned(),
+        false => dirs::get_workspace_file_path(true).ok()?,
+    };
+
+    file::parse(&path).ok()
+}
+
+fn candidate(value: String, help: &str) -> CompletionCandidate
+{
+    CompletionCandidate::new(value).help(Some(help.to_owned().into()))
+}
+
+fn list_candidates(file: &File) -> Vec<CompletionCandidate>
+{
+    file.lists()
+        .iter()
+        .map(|list| {
+            candidate(
+                list.name.clone(),
+                list.kind.as_ref().map_or("list", |kind| kind.label()),
+            )
+        })
+        .collect()
+}
+
+fn project_candidates(file: &File) -> Vec<CompletionCandidate>
+{
+    file.all_projects()
+        .into_iter()
+        .map(|(path, project)| candidate(path.to_string(), &project.name))
+        .collect()
+}
+
+fn task_candidates(file: &File) -> Vec<CompletionCandidate>
+{
+    file.all_tasks()
+        .into_iter()
+        .map(|(path, task)| candidate(path.to_string(), &task.name))
+        .collect()
+}
+
+/// Suggest the names of the lists
+pub fn lists() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| list_candidates(&file))
+        .unwrap_or_default()
+}
+
+/// Suggest the paths of the projects
+pub fn projects() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| project_candidates(&file))
+        .unwrap_or_default()
+}
+
+/// Suggest the paths of the tasks
+pub fn tasks() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| task_candidates(&file))
+        .unwrap_or_default()
+}
+
+/// Suggest the paths of the places tasks and projects can be put in: lists
+/// and projects
+pub fn containers() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| {
+            let mut candidates = list_candidates(&file);
+            candidates.extend(project_candidates(&file));
+            candidates
+        })
+        .unwrap_or_default()
+}
+
+/// Suggest the names and paths of every list, project and task
+pub fn items() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| {
+            let mut candidates = list_candidates(&file);
+            candidates.extend(project_candidates(&file));
+            candidates.extend(task_candidates(&file));
+            candidates
+        })
+        .unwrap_or_default()
+}
+
+/// Suggest the names of the areas of focus
+pub fn areas() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| {
+            file.areas
+                .iter()
+                .map(|area| {
+                    candidate(
+                        area.name.clone(),
+                        area.description.as_deref().unwrap_or("area"),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Suggest the names of the goals
+pub fn goals() -> Vec<CompletionCandidate>
+{
+    load_workspace()
+        .map(|file| {
+            file.goals
+                .iter()
+                .map(|goal| {
+                    candidate(
+                        goal.name.clone(),
+                        goal.area.as_deref().unwrap_or("goal"),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Suggest the names of the settings
+pub fn settings() -> Vec<CompletionCandidate>
+{
+    config::keys()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Print the script that makes `shell` ask `vgtd` for completions
+pub fn write_registration(shell: Shell) -> EResult<()>
+{
+    let shells = Shells::builtins();
+
+    let completer = match shells.completer(&shell.to_string())
+    {
+        Some(completer) => completer,
+        None =>
+        {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Completions are not available for {}.", shell),
+            )));
+        }
+    };
+
+    let executable = std::env::current_exe()?;
+
+    completer.write_registration(
+        COMPLETE_VAR,
+        "vgtd",
+        "vgtd",
+        &executable.to_string_lossy(),
+        &mut io::stdout(),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,589 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use chrono::format::Item;
+use chrono::format::StrftimeItems;
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::dates;
+use crate::dirs;
+use crate::gtd::List;
+use crate::gtd::ListKind;
+use crate::tos;
+use crate::ColorChoice;
+use crate::EResult;
+use crate::SortOrder;
+use crate::Theme;
+
+/// The lists `init` creates unless configured otherwise
+const DEFAULT_LISTS: [&str; 5] = [
+    "inbox:inbox",
+    "next:next-actions",
+    "waiting:waiting-for",
+    "someday:someday",
+    "done:done",
+];
+
+/// The settings that are not colours
+const KEYS: [&str; 7] = [
+    "lists",
+    "capture-list",
+    "date-format",
+    "confirm",
+    "sort",
+    "theme",
+    "pager",
+];
+
+const COLORS_PREFIX: &str = "colors.";
+
+/// The user's preferences, read from the user's configuration file and from
+/// the configuration file of the workspace, which takes precedence
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config
+{
+    /// The lists `init` creates, as "name" or "name:kind"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lists: Option<Vec<String>>,
+    /// The list `add` captures items at instead of the inbox
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture_list: Option<String>,
+    /// Colours replacing those of the default palette, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, String>,
+    /// How dates are shown, in strftime syntax
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    /// Whether to ask before removing things
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<bool>,
+    /// The order projects and tasks are shown in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SortOrder>,
+    /// The colours output is printed with, before `colors` is applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Theme>,
+    /// Whether to show output taller than the terminal through the pager
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pager: Option<bool>,
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn invalid_value(message: String) -> Box<io::Error>
+{
+    Box::new(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+
+/// Split a "name:kind" list definition
+fn parse_list(source: &str) -> EResult<List>
+{
+    match source.split_once(':')
+    {
+        Some((name, kind)) =>
+        {
+            Ok(List::with_kind(name.to_lowercase(), ListKind::parse(kind)?))
+        }
+        None => Ok(List::new(source.to_lowercase())),
+    }
+}
+
+fn parse_bool(source: &str) -> EResult<bool>
+{
+    match source.to_lowercase().as_str()
+    {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ =>
+        {
+            Err(invalid_value(format!(
+                "Expected yes or no, found \"{}\".",
+                source
+            )))
+        }
+    }
+}
+
+fn check_date_format(format: &str) -> EResult<()>
+{
+    if format.is_empty()
+        || StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+        || dates::try_format_date(&dates::today(), format).is_none()
+    {
+        return Err(invalid_value(format!(
+            "\"{}\" is not a valid date format.",
+            format
+        )));
+    }
+
+    Ok(())
+}
+
+/// The name a setting such as `sort` or `theme` is given on the command line
+fn value_name(value: impl ValueEnum) -> String
+{
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_owned())
+        .unwrap_or_default()
+}
+
+/// Parse the value of a setting such as `sort` or `theme`
+fn parse_value<T: ValueEnum>(source: &str) -> EResult<T>
+{
+    if let Ok(value) = T::from_str(source, true)
+    {
+        return Ok(value);
+    }
+
+    let names: Vec<String> = T::value_variants()
+        .iter()
+        .cloned()
+        .map(value_name)
+        .collect();
+
+    Err(invalid_value(format!(
+        "Expected one of {}, found \"{}\".",
+        names.join(", "),
+        source
+    )))
+}
+
+impl Config
+{
+    /// Read the settings at `path`, without checking their values, so that
+    /// `change_setting` can still fix a file that was edited by hand
+    fn parse(path: &Path) -> EResult<Self>
+    {
+        if !path.exists()
+        {
+            return Ok(Config::default());
+        }
+
+        let config: Config = match toml::from_str(&fs::read_to_string(path)?)
+        {
+            Ok(config) => config,
+            Err(error) =>
+            {
+                return Err(invalid_value(format!(
+                    "Invalid configuration file {}: {}",
+                    path.display(),
+                    error.message()
+                )));
+            }
+        };
+
+        Ok(config)
+    }
+
+    /// Read the settings at `path`, checking the values that `set_value`
+    /// would have refused, as the file may have been edited by hand
+    fn read(path: &Path) -> EResult<Self>
+    {
+        let config = Config::parse(path)?;
+
+        if let Some(format) = &config.date_format
+        {
+            if let Err(error) = check_date_format(format)
+            {
+                return Err(invalid_value(format!(
+                    "Invalid configuration file {}: {}",
+                    path.display(),
+                    error
+                )));
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn write(&self, path: &Path) -> EResult<()>
+    {
+        if let Some(parent) = path.parent()
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, toml::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Apply the settings of `other` on top of these ones
+    fn merge(mut self, other: Config) -> Self
+    {
+        self.lists = other.lists.or(self.lists);
+        self.capture_list = other.capture_list.or(self.capture_list);
+        self.colors.extend(other.colors);
+        self.date_format = other.date_format.or(self.date_format);
+        self.confirm = other.confirm.or(self.confirm);
+        self.sort = other.sort.or(self.sort);
+        self.theme = other.theme.or(self.theme);
+        self.pager = other.pager.or(self.pager);
+
+        self
+    }
+
+    /// The lists `init` creates
+    pub fn default_lists(&self) -> EResult<Vec<List>>
+    {
+        match &self.lists
+        {
+            Some(lists) => lists.iter().map(|list| parse_list(list)).collect(),
+            None =>
+            {
+                DEFAULT_LISTS.iter().map(|list| parse_list(list)).collect()
+            }
+        }
+    }
+
+    pub fn date_format(&self) -> &str
+    {
+        self.date_format.as_deref().unwrap_or(dates::DATE_FORMAT)
+    }
+
+    pub fn confirm(&self) -> bool { self.confirm.unwrap_or(true) }
+
+    pub fn sort(&self) -> SortOrder { self.sort.unwrap_or_default() }
+
+    pub fn theme(&self) -> Theme { self.theme.unwrap_or_default() }
+
+    pub fn pager(&self) -> bool { self.pager.unwrap_or(true) }
+
+    /// The palette of the theme with the configured colours applied
+    pub fn palette(&self) -> EResult<tos::Palette>
+    {
+        let mut palette = tos::Palette::of(self.theme());
+
+        for (key, color) in &self.colors
+        {
+            palette.set(key, tos::parse_color(color)?)?;
+        }
+
+        Ok(palette)
+    }
+
+    /// The value a setting was given in this file, if any
+    fn value(&self, key: &str) -> EResult<Option<String>>
+    {
+        if let Some(color) = key.strip_prefix(COLORS_PREFIX)
+        {
+            check_color_key(color)?;
+
+            return Ok(self.colors.get(color).cloned());
+        }
+
+        Ok(match key
+        {
+            "lists" => self.lists.as_ref().map(|lists| lists.join(", ")),
+            "capture-list" => self.capture_list.clone(),
+            "date-format" => self.date_format.clone(),
+            "confirm" =>
+            {
+                self.confirm.map(|confirm| {
+                    if confirm { "yes" } else { "no" }.to_owned()
+                })
+            }
+            "sort" => self.sort.map(value_name),
+            "theme" => self.theme.map(value_name),
+            "pager" =>
+            {
+                self.pager
+                    .map(|pager| if pager { "yes" } else { "no" }.to_owned())
+            }
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    /// Change a setting, or remove it if `value` is `None`
+    fn set_value(&mut self, key: &str, value: Option<&str>) -> EResult<()>
+    {
+        if let Some(color) = key.strip_prefix(COLORS_PREFIX)
+        {
+            check_color_key(color)?;
+
+            match value
+            {
+                Some(value) =>
+                {
+                    tos::parse_color(value)?;
+                    self.colors.insert(color.to_owned(), value.to_owned());
+                }
+                None =>
+                {
+                    self.colors.remove(color);
+                }
+            }
+
+            return Ok(());
+        }
+
+        match key
+        {
+            "lists" =>
+            {
+                self.lists = value
+                    .map(|value| {
+                        let lists: Vec<String> = value
+                            .split([',', ' '])
+                            .filter(|list| !list.is_empty())
+                            .map(str::to_owned)
+                            .collect();
+
+                        for list in &lists
+                        {
+                            parse_list(list)?;
+                        }
+
+                        Ok::<_, Box<dyn std::error::Error>>(lists)
+                    })
+                    .transpose()?
+            }
+            "capture-list" =>
+            {
+                self.capture_list = value.map(|value| value.to_lowercase())
+            }
+            "date-format" =>
+            {
+                if let Some(value) = value
+                {
+                    check_date_format(value)?;
+                }
+
+                self.date_format = value.map(str::to_owned);
+            }
+            "confirm" => self.confirm = value.map(parse_bool).transpose()?,
+            "sort" => self.sort = value.map(parse_value).transpose()?,
+            "theme" => self.theme = value.map(parse_value).transpose()?,
+            "pager" => self.pager = value.map(parse_bool).transpose()?,
+            _ => return Err(unknown_key(key)),
+        }
+
+        Ok(())
+    }
+}
+
+fn unknown_key(key: &str) -> Box<io::Error>
+{
+    invalid_value(format!(
+        "Unknown setting \"{}\" (see `vgtd config list`).",
+        key
+    ))
+}
+
+fn check_color_key(key: &str) -> EResult<()>
+{
+    if tos::Palette::keys().contains(&key)
+    {
+        return Ok(());
+    }
+
+    Err(invalid_value(format!(
+        "There is no colour named \"{}\".",
+        key
+    )))
+}
+
+/// The value of a setting when it is not configured anywhere, colours coming
+/// from `theme`
+fn default_value(key: &str, theme: Theme) -> String
+{
+    if let Some(color) = key.strip_prefix(COLORS_PREFIX)
+    {
+        return tos::Palette::of(theme)
+            .get(color)
+            .map(tos::color_name)
+            .unwrap_or_default();
+    }
+
+    match key
+    {
+        "lists" => DEFAULT_LISTS.join(", "),
+        "capture-list" => "the inbox".to_owned(),
+        "date-format" => dates::DATE_FORMAT.to_owned(),
+        "confirm" => "yes".to_owned(),
+        "sort" => value_name(SortOrder::default()),
+        "theme" => value_name(Theme::default()),
+        "pager" => "yes".to_owned(),
+        _ => String::new(),
+    }
+}
+
+/// The name of every setting
+pub fn keys() -> Vec<String>
+{
+    KEYS.iter()
+        .map(|key| key.to_string())
+        .chain(
+            tos::Palette::keys()
+                .into_iter()
+                .map(|color| format!("{}{}", COLORS_PREFIX, color)),
+        )
+        .collect()
+}
+
+fn user_config() -> EResult<Config>
+{
+    match dirs::get_config_file_path()
+    {
+        Some(path) => Config::read(&path),
+        None => Ok(Config::default()),
+    }
+}
+
+/// Read the configuration of the user and of the workspace at
+/// `workspace_path`, and use it from now on
+pub fn load(workspace_path: &str) -> EResult<()>
+{
+    let config = user_config()?.merge(Config::read(
+        &dirs::get_workspace_config_file_path(workspace_path),
+    )?);
+
+    tos::use_palette(config.palette()?);
+
+    if config.theme() == Theme::Monochrome
+    {
+        tos::use_colors(ColorChoice::Never);
+    }
+
+    let _ = CONFIG.set(config);
+
+    Ok(())
+}
+
+/// The configuration in use, which is the default one unless `load` was
+/// called at startup
+pub fn get() -> &'static Config { CONFIG.get_or_init(Config::default) }
+
+/// Print the value of every setting and where it was set
+pub fn show_settings(workspace_path: &str) -> EResult<()>
+{
+    let user = user_config()?;
+    let workspace =
+        Config::read(&dirs::get_workspace_config_file_path(workspace_path))?;
+
+    let theme = workspace.theme.or(user.theme).unwrap_or_default();
+
+    let mut output = tos::OutputBlock::new();
+
+    output.insert_line(&tos::format_section_name("Settings"), 0);
+
+    for key in keys()
+    {
+        let (value, source) = match (workspace.value(&key)?, user.value(&key)?)
+        {
+            (Some(value), _) => (value, " (workspace)"),
+            (None, Some(value)) => (value, " (user)"),
+            (None, None) => (default_value(&key, theme), ""),
+        };
+
+        output.insert_line(
+            &format!(
+                "{} = {}{}",
+                key.color(tos::palette().identifier),
+                value,
+                source
+            ),
+            1,
+        );
+    }
+
+    output.send();
+
+    Ok(())
+}
+
+/// Print the value a setting has in the workspace at `workspace_path`
+pub fn show_setting(workspace_path: &str, key: &str) -> EResult<()>
+{
+    let user = user_config()?;
+    let workspace =
+        Config::read(&dirs::get_workspace_config_file_path(workspace_path))?;
+
+    let theme = workspace.theme.or(user.theme).unwrap_or_default();
+
+    let value = match workspace.value(key)?
+    {
+        Some(value) => value,
+        None =>
+        {
+            user.value(key)?
+                .unwrap_or_else(|| default_value(key, theme))
+        }
+    };
+
+    println!("{}", value);
+
+    Ok(())
+}
+
+/// Change a setting in the user's configuration, or in the configuration of
+/// the workspace at `workspace_path` if `workspace` is set; "none" removes it
+pub fn change_setting(
+    workspace_path: &str,
+    key: &str,
+    value: &str,
+    workspace: bool,
+) -> EResult<()>
+{
+    let path: PathBuf = if workspace
+    {
+        dirs::get_workspace_config_file_path(workspace_path)
+    }
+    else
+    {
+        match dirs::get_config_file_path()
+        {
+            Some(path) => path,
+            None =>
+            {
+                return Err(Box::new(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Could not locate the configuration directory.",
+                )));
+            }
+        }
+    };
+
+    let mut config = Config::parse(&path)?;
+
+    let value = match value.trim()
+    {
+        value if value.to_lowercase() == "none" => None,
+        value => Some(value),
+    };
+
+    config.set_value(key, value)?;
+    config.write(&path)?;
+
+    tos::send_success(&match value
+    {
+        Some(value) =>
+        {
+            format!(
+                "Setting {} set to {} in {}.",
+                key.color(tos::palette().identifier),
+                value,
+                path.display()
+            )
+        }
+        None =>
+        {
+            format!(
+                "Setting {} removed from {}.",
+                key.color(tos::palette().identifier),
+                path.display()
+            )
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,315 @@
+use std::fmt::Write;
+use std::io;
+
+use chrono::Datelike;
+use chrono::Days;
+use chrono::Duration;
+use chrono::Local;
+use chrono::Months;
+use chrono::NaiveDate;
+use chrono::Weekday;
+
+use crate::config;
+use crate::EResult;
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+pub fn today() -> NaiveDate { Local::now().date_naive() }
+
+/// Parse a date relative to the current day; see `parse_date_from`
+pub fn parse_date(source: &str) -> EResult<NaiveDate>
+{
+    parse_date_from(source, today())
+}
+
+fn parse_weekday(source: &str) -> Option<Weekday>
+{
+    match source
+    {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Get the first day after `today` that falls on `weekday`
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate
+{
+    let days_ahead = (weekday.num_days_from_monday() + 7
+        - today.weekday().num_days_from_monday())
+        % 7;
+
+    today
+        + Duration::days(
+            if days_ahead == 0
+            {
+                7
+            }
+            else
+            {
+                days_ahead as i64
+            },
+        )
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate>
+{
+    let (next_year, next_month) = if month == 12
+    {
+        (year + 1, 1)
+    }
+    else
+    {
+        (year, month + 1)
+    };
+
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+/// Move `amount` units (`d`ays, `w`eeks, `m`onths or `y`ears) from `date`
+fn add_offset(date: NaiveDate, amount: u32, unit: &str) -> Option<NaiveDate>
+{
+    match unit
+    {
+        "d" | "day" | "days" =>
+        {
+            date.checked_add_days(Days::new(amount.into()))
+        }
+        "w" | "week" | "weeks" =>
+        {
+            date.checked_add_signed(Duration::weeks(amount.into()))
+        }
+        "m" | "month" | "months" =>
+        {
+            date.checked_add_months(Months::new(amount))
+        }
+        "y" | "year" | "years" =>
+        {
+            date.checked_add_months(Months::new(amount.checked_mul(12)?))
+        }
+        _ => None,
+    }
+}
+
+/// Parse an absolute date without a year, such as `23 oct` or `oct 23`,
+/// picking its next occurrence (today included)
+fn parse_day_and_month(source: &str, today: NaiveDate) -> Option<NaiveDate>
+{
+    let with_year = |year: i32| {
+        ["%d %b %Y", "%b %d %Y", "%d %B %Y", "%B %d %Y"]
+            .iter()
+            .find_map(|format| {
+                NaiveDate::parse_from_str(
+                    &format!("{} {}", source, year),
+                    format,
+                )
+                .ok()
+            })
+    };
+
+    // A leap day can be up to eight years away (e.g. from 2097 to 2104)
+    (today.year()..=today.year().saturating_add(8))
+        .find_map(|year| with_year(year).filter(|date| *date >= today))
+}
+
+/// Parse a date, resolving relative dates against `today`
+///
+/// Understands absolute dates (`2026-10-23`, `23 oct`, `23 oct 2027`),
+/// `today`, `tomorrow`, `yesterday`, weekdays (`fri`, meaning the next
+/// Friday), `next monday` (the Monday of next week), `next week`,
+/// `next month`, offsets (`in 3 days`, `in a week`, `2w`, `1m`) and
+/// `end of week`, `end of month` and `end of year`.
+pub fn parse_date_from(source: &str, today: NaiveDate) -> EResult<NaiveDate>
+{
+    let normalized = source
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    let words: Vec<&str> = normalized.split(' ').collect();
+
+    let date = match words.as_slice()
+    {
+        ["today"] | ["now"] => Some(today),
+        ["tomorrow"] => today.succ_opt(),
+        ["yesterday"] => today.pred_opt(),
+        ["next", "week"] => Some(next_weekday(today, Weekday::Mon)),
+        ["next", "month"] =>
+        {
+            last_day_of_month(today.year(), today.month())
+                .and_then(|date| date.succ_opt())
+        }
+        ["next", weekday] =>
+        {
+            parse_weekday(weekday).map(|weekday| {
+                let date = next_weekday(today, weekday);
+
+                // Weekdays of the current week belong to "this" week instead
+                if date.iso_week() == today.iso_week()
+                {
+                    date + Duration::weeks(1)
+                }
+                else
+                {
+                    date
+                }
+            })
+        }
+        ["end", "of", "week"] | ["eow"] =>
+        {
+            Some(
+                today
+                    + Duration::days(
+                        6 - today.weekday().num_days_from_monday() as i64,
+                    ),
+            )
+        }
+        ["end", "of", "month"] | ["eom"] =>
+        {
+            last_day_of_month(today.year(), today.month())
+        }
+        ["end", "of", "year"] | ["eoy"] =>
+        {
+            NaiveDate::from_ymd_opt(today.year(), 12, 31)
+        }
+        ["in", "a", unit] | ["in", "an", unit] => add_offset(today, 1, unit),
+        ["in", amount, unit] =>
+        {
+            amount
+                .parse::<u32>()
+                .ok()
+                .and_then(|amount| add_offset(today, amount, unit))
+        }
+        [word] =>
+        {
+            parse_weekday(word)
+                .map(|weekday| next_weekday(today, weekday))
+                .or_else(|| {
+                    let split = word.find(|c: char| !c.is_ascii_digit())?;
+                    let (amount, unit) = word.split_at(split);
+
+                    add_offset(today, amount.parse().ok()?, unit)
+                })
+                .or_else(|| NaiveDate::parse_from_str(word, DATE_FORMAT).ok())
+                .or_else(|| NaiveDate::parse_from_str(word, "%Y/%m/%d").ok())
+        }
+        [_, _] => parse_day_and_month(&normalized, today),
+        [_, _, _] =>
+        {
+            ["%d %b %Y", "%b %d %Y", "%d %B %Y", "%B %d %Y"]
+                .iter()
+                .find_map(|format| {
+                    NaiveDate::parse_from_str(&normalized, format).ok()
+                })
+        }
+        _ => None,
+    };
+
+    match date
+    {
+        Some(date) => Ok(date),
+        None =>
+        {
+            Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Could not parse date \"{}\" (e.g. 2026-10-23, \
+                     tomorrow, fri, in 3 days).",
+                    source
+                ),
+            )))
+        }
+    }
+}
+
+/// Format `date` with the strftime `format`, or return `None` if the format
+/// can't be applied to a date, e.g. because it asks for the time of day
+pub fn try_format_date(date: &NaiveDate, format: &str) -> Option<String>
+{
+    let mut formatted = String::new();
+
+    write!(formatted, "{}", date.format(format)).ok()?;
+
+    Some(formatted)
+}
+
+/// Format `date` as configured, falling back to `DATE_FORMAT`
+pub fn format_date(date: &NaiveDate) -> String
+{
+    try_format_date(date, config::get().date_format())
+        .unwrap_or_else(|| date.format(DATE_FORMAT).to_string())
+}
+
+/// Parse a duration such as `15m`, `1h`, `1h30m` or `90` into minutes
+pub fn parse_duration(source: &str) -> EResult<u32>
+{
+    let invalid = || -> Box<io::Error> {
+        Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Could not parse duration \"{}\" (e.g. 15m, 1h30m).",
+                source
+            ),
+        ))
+    };
+
+    let source = source.trim().to_lowercase();
+
+    if let Ok(minutes) = source.parse::<u32>()
+    {
+        return Ok(minutes);
+    }
+
+    let mut minutes: u32 = 0;
+    let mut number = String::new();
+
+    for character in source.chars()
+    {
+        if character.is_ascii_digit()
+        {
+            number.push(character);
+            continue;
+        }
+
+        let value: u32 = number.parse().map_err(|_| invalid())?;
+
+        let unit: u32 = match character
+        {
+            'm' => 1,
+            'h' => 60,
+            'd' => 60 * 24,
+            _ => return Err(invalid()),
+        };
+
+        minutes = value
+            .checked_mul(unit)
+            .and_then(|value| minutes.checked_add(value))
+            .ok_or_else(invalid)?;
+
+        number.clear();
+    }
+
+    if !number.is_empty() || source.is_empty()
+    {
+        return Err(invalid());
+    }
+
+    Ok(minutes)
+}
+
+pub fn format_duration(minutes: u32) -> String
+{
+    match (minutes / 60, minutes % 60)
+    {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
@@ -1,8 +1,11 @@
 use std::io;
+use std::path::Path;
+use std::path::PathBuf;
 
 use crate::EResult;
 
 pub const GTD_FILE_PATH: &str = ".gtd.toml";
+pub const WORKSPACE_CONFIG_FILE_NAME: &str = ".gtd.config.toml";
 
 pub fn get_workspace_file_path(global: bool) -> EResult<String>
 {
@@ -34,3 +37,26 @@ pub fn get_workspace_file_path(global: bool) -> EResult<String>
         )));
     }
 }
+
+/// The file where the commands typed into the shell are remembered, if the
+/// data directory of the user can be found
+pub fn get_history_file_path() -> Option<PathBuf>
+{
+    directories::BaseDirs::new()
+        .map(|base_dir| base_dir.data_dir().join("vgtd").join("history"))
+}
+
+/// The file holding the user's configuration, if the configuration directory
+/// of the user can be found
+pub fn get_config_file_path() -> Option<PathBuf>
+{
+    directories::BaseDirs::new()
+        .map(|base_dir| base_dir.config_dir().join("vgtd").join("config.toml"))
+}
+
+/// The file next to the workspace file that overrides the user's
+/// configuration for that workspace
+pub fn get_workspace_config_file_path(workspace_file_path: &str) -> PathBuf
+{
+    Path::new(workspace_file_path).with_file_name(WORKSPACE_CONFIG_FILE_NAME)
+}
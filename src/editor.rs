@@ -0,0 +1,117 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process;
+
+use crate::EResult;
+
+const DEFAULT_EDITOR: &str = "vi";
+const TEMP_FILE_ATTEMPTS: usize = 100;
+
+/// Get the user's preferred editor command, split into the program and its
+/// arguments the way a shell would
+fn editor_command() -> EResult<Vec<String>>
+{
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_default();
+
+    let command = shell_words::split(&editor).map_err(|_| {
+        Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Could not parse the editor command \"{}\".", editor),
+        ))
+    })?;
+
+    if command.is_empty()
+    {
+        Ok(vec![DEFAULT_EDITOR.to_owned()])
+    }
+    else
+    {
+        Ok(command)
+    }
+}
+
+/// Create a new temporary file for the editor, never reusing a file (or a
+/// symlink) that is already there
+fn create_temp_file() -> EResult<(PathBuf, fs::File)>
+{
+    for attempt in 0..TEMP_FILE_ATTEMPTS
+    {
+        let path = env::temp_dir().join(format!(
+            "vgtd-{}-{}.md",
+            process::id(),
+            attempt
+        ));
+
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists =>
+            {
+                continue
+            }
+            Err(error) => return Err(Box::new(error)),
+        }
+    }
+
+    Err(Box::new(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "Could not create a temporary file for the editor.",
+    )))
+}
+
+/// Let the user edit `text` in their editor ($VISUAL or $EDITOR), returning
+/// the edited text
+pub fn edit_text(text: &str) -> EResult<String>
+{
+    let command = editor_command()?;
+    let (path, mut file) = create_temp_file()?;
+
+    let written = file.write_all(text.as_bytes());
+
+    drop(file);
+
+    if let Err(error) = written
+    {
+        fs::remove_file(&path)?;
+
+        return Err(Box::new(error));
+    }
+
+    let status = process::Command::new(&command[0])
+        .args(&command[1..])
+        .arg(&path)
+        .status();
+
+    let edited = fs::read_to_string(&path);
+
+    fs::remove_file(&path)?;
+
+    match status
+    {
+        Ok(status) if status.success() =>
+        {}
+        Ok(_) =>
+        {
+            return Err(Box::new(io::Error::other(
+                "The editor exited with an error; nothing was changed.",
+            )));
+        }
+        Err(error) =>
+        {
+            return Err(Box::new(io::Error::new(
+                error.kind(),
+                format!("Could not open the editor \"{}\".", command[0]),
+            )));
+        }
+    }
+
+    Ok(edited?.trim_end().to_owned())
+}
@@ -1,9 +1,15 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io;
 
+use chrono::NaiveDate;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::itempath::ContainerPath;
+use crate::itempath::TaskPath;
+use crate::recurrence::Recurrence;
+use crate::text::Formattable;
 use crate::EResult;
 
 #[derive(Serialize, Deserialize, Copy, Clone)]
@@ -44,12 +50,202 @@ impl Status
     }
 }
 
+#[derive(
+    Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum Priority
+{
+    A,
+    B,
+    C,
+}
+
+impl Priority
+{
+    pub fn parse(source: &str) -> EResult<Self>
+    {
+        match source.to_lowercase().as_str()
+        {
+            "a" | "1" | "high" => Ok(Priority::A),
+            "b" | "2" | "medium" => Ok(Priority::B),
+            "c" | "3" | "low" => Ok(Priority::C),
+            _ =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("No priority matches \"{}\".", source),
+                )))
+            }
+        }
+    }
+
+    /// Key ordering items from the highest priority to the lowest, with
+    /// unprioritized items last
+    pub fn sort_key(priority: Option<Priority>) -> (bool, Option<Priority>)
+    {
+        (priority.is_none(), priority)
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum Energy
+{
+    Low,
+    Medium,
+    High,
+}
+
+impl Energy
+{
+    pub fn parse(source: &str) -> EResult<Self>
+    {
+        match source.to_lowercase().as_str()
+        {
+            "low" | "l" => Ok(Energy::Low),
+            "medium" | "m" => Ok(Energy::Medium),
+            "high" | "h" => Ok(Energy::High),
+            _ =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("No energy level matches \"{}\".", source),
+                )))
+            }
+        }
+    }
+}
+
+/// Normalize a context name so that `Home` and `@home` are the same context
+pub fn parse_context(source: &str) -> String
+{
+    format!("@{}", source.trim().trim_start_matches('@').to_lowercase())
+}
+
+/// Normalize a tag name so that `Q4` and `#q4` are the same tag
+pub fn parse_tag(source: &str) -> String
+{
+    format!("#{}", source.trim().trim_start_matches('#').to_lowercase())
+}
+
+/// Normalize the name of the person a task is delegated to, so that `bob`
+/// and ` Bob ` are the same person
+pub fn parse_delegate(source: &str) -> String { source.trim().to_titlecase() }
+
+fn is_zero(number: &u64) -> bool { *number == 0 }
+
+/// An item that can be labelled with free-form tags
+pub trait Tagged
+{
+    fn tags(&self) -> &Vec<String>;
+
+    fn tags_mut(&mut self) -> &mut Vec<String>;
+
+    fn has_tag(&self, tag: &str) -> bool
+    {
+        self.tags().iter().any(|t| t == tag)
+    }
+
+    /// Add a tag to the item, returning whether it didn't have it already
+    fn add_tag(&mut self, tag: &str) -> bool
+    {
+        if self.has_tag(tag)
+        {
+            return false;
+        }
+
+        self.tags_mut().push(tag.to_owned());
+
+        true
+    }
+
+    /// Remove a tag from the item, returning whether it had it
+    fn remove_tag(&mut self, tag: &str) -> bool
+    {
+        let count = self.tags().len();
+
+        self.tags_mut().retain(|t| t != tag);
+
+        self.tags().len() != count
+    }
+
+    /// Replace a tag of the item, returning whether it had it
+    fn rename_tag(&mut self, tag: &str, new_tag: &str) -> bool
+    {
+        if !self.remove_tag(tag)
+        {
+            return false;
+        }
+
+        self.add_tag(new_tag);
+
+        true
+    }
+}
+
+/// A small step of a task, too tiny to be a task of its own
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChecklistItem
+{
+    pub name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checked: bool,
+}
+
+impl ChecklistItem
+{
+    pub fn new(name: String) -> Self
+    {
+        Self {
+            name,
+            checked: false,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Task
 {
+    /// A stable identifier for the task, assigned when other tasks first
+    /// refer to it (zero if unassigned)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub id: u64,
     pub name: String,
     pub description: Option<String>,
     pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    /// The day the task resurfaces in the inbox if it is in a someday list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tickler: Option<NaiveDate>,
+    /// The person the task was handed over to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegate: Option<String>,
+    /// The day the task was handed over
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegated: Option<NaiveDate>,
+    /// The day to check on the delegate's progress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_up: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+    /// The estimated time needed to do the task, in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy: Option<Energy>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contexts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The identifiers of the tasks that must be done before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
 }
 
 impl Task
@@ -57,13 +253,138 @@ impl Task
     pub fn new(name: String, description: Option<String>) -> Self
     {
         Self {
+            id: 0,
             name,
             description,
             status: Status::TODO,
+            priority: None,
+            due: None,
+            tickler: None,
+            delegate: None,
+            delegated: None,
+            follow_up: None,
+            recurrence: None,
+            estimate: None,
+            energy: None,
+            contexts: vec![],
+            tags: vec![],
+            depends_on: vec![],
+            checklist: vec![],
         }
     }
 
     pub fn done(&self) -> bool { matches!(self.status, Status::DONE) }
+
+    pub fn checklist_completed(&self) -> usize
+    {
+        self.checklist.iter().filter(|item| item.checked).count()
+    }
+
+    pub fn get_checklist_item_mut_forced(
+        &mut self,
+        index: usize,
+    ) -> EResult<&mut ChecklistItem>
+    {
+        match self.checklist.get_mut(index)
+        {
+            Some(item) => Ok(item),
+            None =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Checklist item not found.",
+                )))
+            }
+        }
+    }
+
+    /// Check whether the task can be done with the given time (in minutes),
+    /// energy and context; unspecified task details fit anything
+    pub fn fits(
+        &self,
+        time: Option<u32>,
+        energy: Option<Energy>,
+        context: Option<&str>,
+    ) -> bool
+    {
+        let fits_time = match (self.estimate, time)
+        {
+            (Some(estimate), Some(time)) => estimate <= time,
+            _ => true,
+        };
+
+        let fits_energy = match (self.energy, energy)
+        {
+            (Some(required), Some(available)) => required <= available,
+            _ => true,
+        };
+
+        let fits_context = match context
+        {
+            Some(context) =>
+            {
+                self.contexts.is_empty()
+                    || self.contexts.iter().any(|c| c == context)
+            }
+            None => true,
+        };
+
+        fits_time && fits_energy && fits_context
+    }
+
+    /// Create the next occurrence of a recurring task that was completed on
+    /// `today`, handing the recurrence rule over to it
+    pub fn next_occurrence(&mut self, today: NaiveDate) -> Option<Task>
+    {
+        let recurrence = self.recurrence.take()?;
+
+        let due = if recurrence.after_completion
+        {
+            recurrence.next_date(today)
+        }
+        else
+        {
+            let mut due = recurrence.next_date(self.due.unwrap_or(today));
+
+            while due <= today
+            {
+                due = recurrence.next_date(due);
+            }
+
+            due
+        };
+
+        Some(Task {
+            id: 0,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            status: Status::TODO,
+            priority: self.priority,
+            due: Some(due),
+            tickler: None,
+            delegate: self.delegate.clone(),
+            delegated: None,
+            follow_up: None,
+            recurrence: Some(recurrence),
+            estimate: self.estimate,
+            energy: self.energy,
+            contexts: self.contexts.clone(),
+            tags: self.tags.clone(),
+            depends_on: self.depends_on.clone(),
+            checklist: self
+                .checklist
+                .iter()
+                .map(|item| ChecklistItem::new(item.name.clone()))
+                .collect(),
+        })
+    }
+}
+
+impl Tagged for Task
+{
+    fn tags(&self) -> &Vec<String> { &self.tags }
+
+    fn tags_mut(&mut self) -> &mut Vec<String> { &mut self.tags }
 }
 
 pub trait TaskContainer
@@ -141,25 +462,42 @@ pub trait TaskContainer
     {
         self.tasks().iter().filter(|t| t.done()).count()
     }
-
-    fn all_tasks_done(&self) -> bool
-    {
-        if self.tasks().is_empty()
-        {
-            return false;
-        }
-        else
-        {
-            self.tasks_completed() == self.tasks().len()
-        }
-    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Project
 {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// If set, the project's tasks must be done in order
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sequential: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The name of the area of focus the project belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    /// The name of the goal the project works towards
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    /// The day the project resurfaces in the inbox if it is in a someday list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tickler: Option<NaiveDate>,
+    /// What done looks like for the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Links and file paths to support material
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
+    /// The day the project was last looked at in a weekly review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reviewed: Option<NaiveDate>,
     tasks: Vec<Task>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    projects: Vec<Project>,
 }
 
 impl Project
@@ -168,17 +506,81 @@ impl Project
     {
         Self {
             name,
+            priority: None,
+            sequential: false,
+            tags: vec![],
+            area: None,
+            goal: None,
+            tickler: None,
+            outcome: None,
+            notes: None,
+            references: vec![],
+            last_reviewed: None,
             tasks: vec![],
+            projects: vec![],
         }
     }
 
-    pub fn status(&self) -> Status
+    /// Count the tasks of the project and of all of its sub-projects
+    pub fn total_tasks(&self) -> usize
+    {
+        self.tasks().len()
+            + self
+                .projects()
+                .iter()
+                .map(|p| p.total_tasks())
+                .sum::<usize>()
+    }
+
+    /// Count the done tasks of the project and of all of its sub-projects
+    pub fn total_tasks_completed(&self) -> usize
+    {
+        self.tasks_completed()
+            + self
+                .projects()
+                .iter()
+                .map(|p| p.total_tasks_completed())
+                .sum::<usize>()
+    }
+
+    /// Get the tasks that can be worked on right now, along with their
+    /// indexes: only the first TODO task of sequential projects, or all TODO
+    /// tasks of parallel ones
+    pub fn available_tasks(&self) -> Vec<(usize, &Task)>
     {
-        if self.tasks().is_empty()
+        let todo = self.tasks().iter().enumerate().filter(|(_, t)| !t.done());
+
+        if self.sequential
         {
-            Status::TODO
+            todo.take(1).collect()
+        }
+        else
+        {
+            todo.collect()
         }
-        else if self.tasks().iter().any(|t| !t.done())
+    }
+
+    /// Check whether the project has no TODO task to move it forward,
+    /// including within its sub-projects
+    pub fn stalled(&self) -> bool
+    {
+        self.total_tasks_completed() == self.total_tasks()
+    }
+
+    /// Check whether any of the project's tasks or sub-projects, at any
+    /// depth, has the tag
+    pub fn contains_tag(&self, tag: &str) -> bool
+    {
+        self.tasks().iter().any(|t| t.has_tag(tag))
+            || self
+                .projects()
+                .iter()
+                .any(|p| p.has_tag(tag) || p.contains_tag(tag))
+    }
+
+    pub fn status(&self) -> Status
+    {
+        if self.total_tasks() == 0 || !self.stalled()
         {
             Status::TODO
         }
@@ -189,6 +591,13 @@ impl Project
     }
 }
 
+impl Tagged for Project
+{
+    fn tags(&self) -> &Vec<String> { &self.tags }
+
+    fn tags_mut(&mut self) -> &mut Vec<String> { &mut self.tags }
+}
+
 impl TaskContainer for Project
 {
     fn tasks(&self) -> &Vec<Task> { &self.tasks }
@@ -196,6 +605,13 @@ impl TaskContainer for Project
     fn tasks_mut(&mut self) -> &mut Vec<Task> { &mut self.tasks }
 }
 
+impl ProjectContainer for Project
+{
+    fn projects(&self) -> &Vec<Project> { &self.projects }
+
+    fn projects_mut(&mut self) -> &mut Vec<Project> { &mut self.projects }
+}
+
 pub trait ProjectContainer
 {
     fn projects(&self) -> &Vec<Project>;
@@ -237,7 +653,7 @@ pub trait ProjectContainer
             {
                 return Err(Box::new(io::Error::new(
                     io::ErrorKind::NotFound,
-                    "Task not found.",
+                    "Project not found.",
                 )));
             }
         }
@@ -278,7 +694,7 @@ pub trait ProjectContainer
     {
         self.projects()
             .iter()
-            .filter(|p| p.all_tasks_done())
+            .filter(|p| matches!(p.status(), Status::DONE))
             .count()
     }
 
@@ -288,10 +704,71 @@ pub trait ProjectContainer
     }
 }
 
+/// The role a list plays in the GTD system
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListKind
+{
+    Inbox,
+    NextActions,
+    WaitingFor,
+    Someday,
+    Reference,
+    Done,
+}
+
+impl ListKind
+{
+    pub fn parse(source: &str) -> EResult<Self>
+    {
+        match source.to_lowercase().as_str()
+        {
+            "inbox" | "in" => Ok(ListKind::Inbox),
+            "next-actions" | "next" => Ok(ListKind::NextActions),
+            "waiting-for" | "waiting" => Ok(ListKind::WaitingFor),
+            "someday" | "maybe" | "someday-maybe" => Ok(ListKind::Someday),
+            "reference" => Ok(ListKind::Reference),
+            "done" => Ok(ListKind::Done),
+            _ =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("No list kind matches \"{}\".", source),
+                )))
+            }
+        }
+    }
+
+    /// Check whether the items of lists of this kind are actions the user
+    /// can take right now
+    pub fn is_actionable(&self) -> bool
+    {
+        matches!(self, ListKind::Inbox | ListKind::NextActions)
+    }
+
+    /// A human-readable name for the kind
+    pub fn label(&self) -> &'static str
+    {
+        match self
+        {
+            ListKind::Inbox => "inbox",
+            ListKind::NextActions => "next actions",
+            ListKind::WaitingFor => "waiting for",
+            ListKind::Someday => "someday/maybe",
+            ListKind::Reference => "reference",
+            ListKind::Done => "done",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct List
 {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ListKind>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     tasks: Vec<Task>,
     projects: Vec<Project>,
 }
@@ -302,10 +779,44 @@ impl List
     {
         Self {
             name,
+            kind: None,
+            tags: vec![],
             tasks: vec![],
             projects: vec![],
         }
     }
+
+    pub fn with_kind(name: String, kind: ListKind) -> Self
+    {
+        let mut list = Self::new(name);
+        list.kind = Some(kind);
+        list
+    }
+
+    /// Check whether the list's items are actions that can be taken right
+    /// now (lists without a kind are)
+    pub fn is_actionable(&self) -> bool
+    {
+        self.kind.is_none_or(|kind| kind.is_actionable())
+    }
+
+    /// Check whether any of the list's tasks or projects, at any depth, has
+    /// the tag
+    pub fn contains_tag(&self, tag: &str) -> bool
+    {
+        self.tasks().iter().any(|t| t.has_tag(tag))
+            || self
+                .projects()
+                .iter()
+                .any(|p| p.has_tag(tag) || p.contains_tag(tag))
+    }
+}
+
+impl Tagged for List
+{
+    fn tags(&self) -> &Vec<String> { &self.tags }
+
+    fn tags_mut(&mut self) -> &mut Vec<String> { &mut self.tags }
 }
 
 impl TaskContainer for List
@@ -338,7 +849,7 @@ pub trait ListContainer
         self.lists_mut().iter_mut().find(|list| list.name == name)
     }
 
-    fn get_list_forced(&mut self, name: &str) -> EResult<&List>
+    fn get_list_forced(&self, name: &str) -> EResult<&List>
     {
         match self.get_list(name)
         {
@@ -392,10 +903,65 @@ pub trait ListContainer
     fn push_list(&mut self, list: List) -> () { self.lists_mut().push(list); }
 }
 
+/// An area of focus: a sphere of life or work to be kept up to standard
+#[derive(Serialize, Deserialize)]
+pub struct Area
+{
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A goal to be achieved within the next year or two
+#[derive(Serialize, Deserialize)]
+pub struct Goal
+{
+    pub name: String,
+    /// The name of the area of focus the goal belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct File
 {
     pub lists: Vec<List>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub areas: Vec<Area>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    /// The identifier the next task referred to gets, so that the ones of
+    /// removed tasks are never handed out again
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub next_id: u64,
+}
+
+/// The tasks of a workspace that have an identifier, looked up by it, so that
+/// the blockers of many tasks can be found without walking the workspace for
+/// each of them
+pub struct TaskIndex<'a>
+{
+    tasks: HashMap<u64, &'a Task>,
+}
+
+impl<'a> TaskIndex<'a>
+{
+    pub fn get(&self, id: u64) -> Option<&'a Task>
+    {
+        self.tasks.get(&id).copied()
+    }
+
+    /// Get the TODO tasks that `task` is waiting on
+    pub fn open_blockers(&self, task: &Task) -> Vec<&'a Task>
+    {
+        task.depends_on
+            .iter()
+            .filter_map(|id| self.get(*id))
+            .filter(|blocker| !blocker.done())
+            .collect()
+    }
 }
 
 impl File
@@ -411,6 +977,536 @@ impl File
 
         Ok(())
     }
+
+    pub fn get_project(&self, path: &ContainerPath) -> EResult<&Project>
+    {
+        let mut project: Option<&Project> = None;
+
+        for index in &path.project_indexes
+        {
+            project = Some(match project
+            {
+                Some(parent) => parent.get_project_forced(*index)?,
+                None =>
+                {
+                    self.get_list_forced(&path.list_name)?
+                        .get_project_forced(*index)?
+                }
+            });
+        }
+
+        match project
+        {
+            Some(project) => Ok(project),
+            None =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "No project index provided.",
+                )))
+            }
+        }
+    }
+
+    pub fn get_project_mut(
+        &mut self,
+        path: &ContainerPath,
+    ) -> EResult<&mut Project>
+    {
+        let (first, rest) = match path.project_indexes.split_first()
+        {
+            Some(indexes) => indexes,
+            None =>
+            {
+                return Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "No project index provided.",
+                )));
+            }
+        };
+
+        let mut project = self
+            .get_list_mut_forced(&path.list_name)?
+            .get_project_mut_forced(*first)?;
+
+        for index in rest
+        {
+            project = project.get_project_mut_forced(*index)?;
+        }
+
+        Ok(project)
+    }
+
+    pub fn get_container(
+        &self,
+        path: &ContainerPath,
+    ) -> EResult<&dyn TaskContainer>
+    {
+        if path.is_project()
+        {
+            Ok(self.get_project(path)?)
+        }
+        else
+        {
+            Ok(self.get_list_forced(&path.list_name)?)
+        }
+    }
+
+    pub fn get_container_mut(
+        &mut self,
+        path: &ContainerPath,
+    ) -> EResult<&mut dyn TaskContainer>
+    {
+        if path.is_project()
+        {
+            Ok(self.get_project_mut(path)?)
+        }
+        else
+        {
+            Ok(self.get_list_mut_forced(&path.list_name)?)
+        }
+    }
+
+    pub fn get_project_container(
+        &self,
+        path: &ContainerPath,
+    ) -> EResult<&dyn ProjectContainer>
+    {
+        if path.is_project()
+        {
+            Ok(self.get_project(path)?)
+        }
+        else
+        {
+            Ok(self.get_list_forced(&path.list_name)?)
+        }
+    }
+
+    pub fn get_project_container_mut(
+        &mut self,
+        path: &ContainerPath,
+    ) -> EResult<&mut dyn ProjectContainer>
+    {
+        if path.is_project()
+        {
+            Ok(self.get_project_mut(path)?)
+        }
+        else
+        {
+            Ok(self.get_list_mut_forced(&path.list_name)?)
+        }
+    }
+
+    pub fn get_task(&self, path: &TaskPath) -> EResult<&Task>
+    {
+        self.get_container(&path.container())?
+            .get_task_forced(path.task_index)
+    }
+
+    pub fn get_task_mut(&mut self, path: &TaskPath) -> EResult<&mut Task>
+    {
+        self.get_container_mut(&path.container())?
+            .get_task_mut_forced(path.task_index)
+    }
+
+    /// Get every project in the workspace, including sub-projects, along
+    /// with its path
+    pub fn all_projects(&self) -> Vec<(ContainerPath, &Project)>
+    {
+        fn collect<'a>(
+            projects: &'a [Project],
+            parent: &ContainerPath,
+            found: &mut Vec<(ContainerPath, &'a Project)>,
+        )
+        {
+            for (index, project) in projects.iter().enumerate()
+            {
+                let path = parent.child(index);
+                found.push((path.clone(), project));
+                collect(project.projects(), &path, found);
+            }
+        }
+
+        let mut projects = vec![];
+
+        for list in self.lists()
+        {
+            collect(
+                list.projects(),
+                &ContainerPath {
+                    list_name: list.name.clone(),
+                    project_indexes: vec![],
+                },
+                &mut projects,
+            );
+        }
+
+        projects
+    }
+
+    /// Get every task in the workspace along with its path
+    pub fn all_tasks(&self) -> Vec<(TaskPath, &Task)>
+    {
+        let mut tasks = vec![];
+
+        for (path, project) in self.all_projects()
+        {
+            for (index, task) in project.tasks().iter().enumerate()
+            {
+                tasks.push((path.task(index), task));
+            }
+        }
+
+        for list in self.lists()
+        {
+            for (index, task) in list.tasks().iter().enumerate()
+            {
+                tasks.push((
+                    TaskPath {
+                        list_name: list.name.clone(),
+                        project_indexes: vec![],
+                        task_index: index,
+                    },
+                    task,
+                ));
+            }
+        }
+
+        tasks
+    }
+
+    /// Get the first list of the given kind
+    pub fn list_of_kind(&self, kind: ListKind) -> Option<&List>
+    {
+        self.lists().iter().find(|list| list.kind == Some(kind))
+    }
+
+    /// Get the first list of the given kind, creating a list with
+    /// `default_name` and that kind if there is none
+    pub fn list_of_kind_mut(
+        &mut self,
+        kind: ListKind,
+        default_name: &str,
+    ) -> &mut List
+    {
+        let position = match self
+            .lists()
+            .iter()
+            .position(|list| list.kind == Some(kind))
+        {
+            Some(position) => position,
+            None =>
+            {
+                self.push_list(List::with_kind(default_name.to_owned(), kind));
+                self.lists.len() - 1
+            }
+        };
+
+        &mut self.lists[position]
+    }
+
+    /// Get the inbox: the first list of the inbox kind, or the list named
+    /// "inbox" if no list has that kind
+    pub fn inbox_name(&self) -> Option<String>
+    {
+        self.list_of_kind(ListKind::Inbox)
+            .or_else(|| self.get_list("inbox"))
+            .map(|list| list.name.clone())
+    }
+
+    /// Move the tasks and projects of someday lists whose tickler date has
+    /// come to the inbox, returning the amount of items moved
+    pub fn resurface_tickled(&mut self, today: NaiveDate) -> usize
+    {
+        let inbox_name = match self.inbox_name()
+        {
+            Some(name) => name,
+            None => return 0,
+        };
+
+        let is_due = |tickler: Option<NaiveDate>| {
+            tickler.is_some_and(|tickler| tickler <= today)
+        };
+
+        let mut tasks: Vec<Task> = vec![];
+        let mut projects: Vec<Project> = vec![];
+
+        for list in self.lists_mut()
+        {
+            if list.kind != Some(ListKind::Someday)
+            {
+                continue;
+            }
+
+            let (due, kept) =
+                list.tasks.drain(..).partition(|t| is_due(t.tickler));
+            list.tasks = kept;
+            tasks.extend::<Vec<Task>>(due);
+
+            let (due, kept) =
+                list.projects.drain(..).partition(|p| is_due(p.tickler));
+            list.projects = kept;
+            projects.extend::<Vec<Project>>(due);
+        }
+
+        let count = tasks.len() + projects.len();
+
+        if let Some(inbox) = self.get_list_mut(&inbox_name)
+        {
+            for mut task in tasks
+            {
+                task.tickler = None;
+                inbox.push_task(task);
+            }
+
+            for mut project in projects
+            {
+                project.tickler = None;
+                inbox.push_project(project);
+            }
+        }
+
+        count
+    }
+
+    pub fn get_area(&self, name: &str) -> EResult<&Area>
+    {
+        match self.areas.iter().find(|area| area.name == name)
+        {
+            Some(area) => Ok(area),
+            None =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Area not found.",
+                )))
+            }
+        }
+    }
+
+    pub fn get_goal(&self, name: &str) -> EResult<&Goal>
+    {
+        match self.goals.iter().find(|goal| goal.name == name)
+        {
+            Some(goal) => Ok(goal),
+            None =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Goal not found.",
+                )))
+            }
+        }
+    }
+
+    /// Get the area of focus a project belongs to, either directly or
+    /// through its goal
+    pub fn project_area<'a>(&'a self, project: &'a Project)
+        -> Option<&'a str>
+    {
+        project.area.as_deref().or_else(|| {
+            self.goals
+                .iter()
+                .find(|goal| project.goal.as_ref() == Some(&goal.name))
+                .and_then(|goal| goal.area.as_deref())
+        })
+    }
+
+    /// Get the projects that are not done yet nor incubating in a someday
+    /// list, along with their paths
+    pub fn active_projects(&self) -> Vec<(ContainerPath, &Project)>
+    {
+        self.all_projects()
+            .into_iter()
+            .filter(|(path, project)| {
+                !matches!(project.status(), Status::DONE)
+                    && self.get_list(&path.list_name).is_some_and(|list| {
+                        list.kind != Some(ListKind::Someday)
+                    })
+            })
+            .collect()
+    }
+
+    /// Get the active projects of an area of focus, along with their paths
+    pub fn active_area_projects(
+        &self,
+        area: &str,
+    ) -> Vec<(ContainerPath, &Project)>
+    {
+        self.active_projects()
+            .into_iter()
+            .filter(|(_, project)| self.project_area(project) == Some(area))
+            .collect()
+    }
+
+    /// Get the areas of focus that have no active project to move them
+    /// forward
+    pub fn neglected_areas(&self) -> Vec<&Area>
+    {
+        self.areas
+            .iter()
+            .filter(|area| self.active_area_projects(&area.name).is_empty())
+            .collect()
+    }
+
+    /// Apply `change` to every project of the workspace, including
+    /// sub-projects
+    pub fn for_each_project_mut(
+        &mut self,
+        change: &mut dyn FnMut(&mut Project),
+    )
+    {
+        fn visit(
+            projects: &mut [Project],
+            change: &mut dyn FnMut(&mut Project),
+        )
+        {
+            for project in projects
+            {
+                change(project);
+                visit(project.projects_mut(), change);
+            }
+        }
+
+        for list in self.lists_mut()
+        {
+            visit(list.projects_mut(), change);
+        }
+    }
+
+    /// Replace a tag on every item of the workspace, returning the amount of
+    /// items that had it
+    pub fn rename_tag(&mut self, tag: &str, new_tag: &str) -> usize
+    {
+        fn rename_in_tasks(
+            tasks: &mut [Task],
+            tag: &str,
+            new_tag: &str,
+        ) -> usize
+        {
+            tasks
+                .iter_mut()
+                .map(|task| task.rename_tag(tag, new_tag))
+                .filter(|renamed| *renamed)
+                .count()
+        }
+
+        fn rename_in_projects(
+            projects: &mut [Project],
+            tag: &str,
+            new_tag: &str,
+        ) -> usize
+        {
+            let mut count = 0;
+
+            for project in projects
+            {
+                if project.rename_tag(tag, new_tag)
+                {
+                    count += 1;
+                }
+
+                count += rename_in_tasks(project.tasks_mut(), tag, new_tag);
+                count +=
+                    rename_in_projects(project.projects_mut(), tag, new_tag);
+            }
+
+            count
+        }
+
+        let mut count = 0;
+
+        for list in self.lists_mut()
+        {
+            if list.rename_tag(tag, new_tag)
+            {
+                count += 1;
+            }
+
+            count += rename_in_tasks(list.tasks_mut(), tag, new_tag);
+            count += rename_in_projects(list.projects_mut(), tag, new_tag);
+        }
+
+        count
+    }
+
+    /// Look up the tasks that have an identifier
+    pub fn task_index(&self) -> TaskIndex<'_>
+    {
+        TaskIndex {
+            tasks: self
+                .all_tasks()
+                .into_iter()
+                .map(|(_, task)| (task.id, task))
+                .filter(|(id, _)| *id != 0)
+                .collect(),
+        }
+    }
+
+    /// Get the identifier of the task at `path`, assigning it one if needed
+    pub fn task_id(&mut self, path: &TaskPath) -> EResult<u64>
+    {
+        // Workspaces written before `next_id` was kept only know the
+        // identifiers of their remaining tasks
+        let highest_id = self
+            .all_tasks()
+            .iter()
+            .map(|(_, task)| task.id)
+            .max()
+            .unwrap_or(0);
+
+        let next_id = self.next_id.max(highest_id + 1);
+
+        let task = self.get_task_mut(path)?;
+
+        if task.id != 0
+        {
+            return Ok(task.id);
+        }
+
+        task.id = next_id;
+        self.next_id = next_id + 1;
+
+        Ok(next_id)
+    }
+
+    /// Get the TODO tasks that `task` is waiting on, looking them up in a
+    /// new `TaskIndex`; use one index when doing this for many tasks
+    pub fn open_blockers(&self, task: &Task) -> Vec<&Task>
+    {
+        self.task_index().open_blockers(task)
+    }
+
+    /// Check whether the task identified by `from` depends on the one
+    /// identified by `target`, either directly or through other tasks
+    pub fn depends_on(&self, from: u64, target: u64) -> bool
+    {
+        let tasks = self.task_index();
+        let mut visited = vec![];
+        let mut pending = vec![from];
+
+        while let Some(id) = pending.pop()
+        {
+            if id == target
+            {
+                return true;
+            }
+
+            if visited.contains(&id)
+            {
+                continue;
+            }
+
+            visited.push(id);
+
+            if let Some(task) = tasks.get(id)
+            {
+                pending.extend(&task.depends_on);
+            }
+        }
+
+        false
+    }
 }
 
 impl ListContainer for File
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::io;
 
 use crate::indexer;
@@ -7,10 +8,25 @@ use crate::EResult;
 
 const PATH_DIVISOR: char = '/';
 
+fn format_sections(list_name: &str, indexes: &[usize]) -> String
+{
+    let mut formatted = tos::format_list_name(list_name);
+
+    for index in indexes
+    {
+        formatted.push(PATH_DIVISOR);
+        formatted.push_str(&tos::format_index(*index));
+    }
+
+    formatted
+}
+
+#[derive(Clone)]
 pub struct TaskPath
 {
     pub list_name: String,
-    pub project_index: Option<usize>,
+    /// The indexes of the projects leading to the task, outermost first
+    pub project_indexes: Vec<usize>,
     pub task_index: usize,
 }
 
@@ -38,24 +54,12 @@ impl TaskPath
             )));
         };
 
-        if sections.len() > 3
-        {
-            return Err(Box::new(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Invalid task path: unrecognized additional path section.",
-            )));
-        };
-
         let list_name: String = sections[0].to_owned();
 
-        let project_index: Option<usize> = if sections.len() == 2
-        {
-            None
-        }
-        else
-        {
-            Some(indexer::identifier_to_index(sections[1])?)
-        };
+        let project_indexes = sections[1..sections.len() - 1]
+            .iter()
+            .map(|section| indexer::identifier_to_index(section))
+            .collect::<EResult<Vec<usize>>>()?;
 
         let task_index: usize = indexer::identifier_to_index(
             sections
@@ -65,10 +69,33 @@ impl TaskPath
 
         Ok(Self {
             list_name,
-            project_index,
+            project_indexes,
             task_index,
         })
     }
+
+    /// Get the path to the container holding the task
+    pub fn container(&self) -> ContainerPath
+    {
+        ContainerPath {
+            list_name: self.list_name.clone(),
+            project_indexes: self.project_indexes.clone(),
+        }
+    }
+}
+
+impl fmt::Display for TaskPath
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.container())?;
+        write!(
+            f,
+            "{}{}",
+            PATH_DIVISOR,
+            indexer::index_to_identifier(self.task_index)
+        )
+    }
 }
 
 impl OutputFormattable for TaskPath
@@ -76,25 +103,21 @@ impl OutputFormattable for TaskPath
     fn tos_format(&self) -> String
     {
         format!(
-            "{}{}/{}",
-            tos::format_list_name(&self.list_name),
-            if let Some(project_index) = self.project_index
-            {
-                format!("/{}", tos::format_index(project_index))
-            }
-            else
-            {
-                String::new()
-            },
+            "{}{}{}",
+            format_sections(&self.list_name, &self.project_indexes),
+            PATH_DIVISOR,
             tos::format_index(self.task_index)
         )
     }
 }
 
+#[derive(Clone)]
 pub struct ContainerPath
 {
     pub list_name: String,
-    pub project_index: Option<usize>,
+    /// The indexes of the projects leading to the container, outermost
+    /// first (empty if the container is the list itself)
+    pub project_indexes: Vec<usize>,
 }
 
 impl ContainerPath
@@ -113,30 +136,91 @@ impl ContainerPath
 
         let sections: Vec<&str> = source.split(PATH_DIVISOR).collect();
 
-        if sections.len() > 2
+        Ok(Self {
+            list_name: sections[0].to_owned(),
+            project_indexes: sections[1..]
+                .iter()
+                .map(|section| indexer::identifier_to_index(section))
+                .collect::<EResult<Vec<usize>>>()?,
+        })
+    }
+
+    /// Parse a path that must point to a project rather than to a list
+    pub fn parse_project(source: &str) -> EResult<Self>
+    {
+        let path = Self::parse(source)?;
+
+        if path.project_indexes.is_empty()
         {
             return Err(Box::new(io::Error::new(
                 io::ErrorKind::InvalidInput,
-                "Invalid container path: unrecognized additional path section.",
+                "No project index provided.",
             )));
-        };
+        }
 
-        if sections.len() > 1
-        {
-            Ok(Self {
-                list_name: sections[0].to_owned(),
-                project_index: Some(indexer::identifier_to_index(
-                    sections[1],
-                )?),
-            })
+        Ok(path)
+    }
+
+    pub fn is_project(&self) -> bool { !self.project_indexes.is_empty() }
+
+    /// Get the path to the container holding this project, along with the
+    /// project's index within it
+    pub fn split_last(&self) -> Option<(ContainerPath, usize)>
+    {
+        let (last, parents) = self.project_indexes.split_last()?;
+
+        Some((
+            ContainerPath {
+                list_name: self.list_name.clone(),
+                project_indexes: parents.to_vec(),
+            },
+            *last,
+        ))
+    }
+
+    /// Get the path to the project at `index` within this container
+    pub fn child(&self, index: usize) -> ContainerPath
+    {
+        let mut path = self.clone();
+        path.project_indexes.push(index);
+        path
+    }
+
+    /// Get the path to the task at `index` within this container
+    pub fn task(&self, index: usize) -> TaskPath
+    {
+        TaskPath {
+            list_name: self.list_name.clone(),
+            project_indexes: self.project_indexes.clone(),
+            task_index: index,
         }
-        else
+    }
+
+    /// Check whether this path points to `other` or to something inside it
+    pub fn starts_with(&self, other: &ContainerPath) -> bool
+    {
+        self.list_name == other.list_name
+            && self.project_indexes.starts_with(&other.project_indexes)
+    }
+}
+
+impl fmt::Display for ContainerPath
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.list_name)?;
+
+        for index in &self.project_indexes
         {
-            Ok(Self {
-                list_name: sections[0].to_owned(),
-                project_index: None,
-            })
+            write!(
+                f,
+                "{}{}",
+                PATH_DIVISOR,
+                indexer::index_to_identifier(*index)
+            )?;
         }
+
+        Ok(())
     }
 }
 
@@ -144,17 +228,6 @@ impl OutputFormattable for ContainerPath
 {
     fn tos_format(&self) -> String
     {
-        format!(
-            "{}{}",
-            tos::format_list_name(&self.list_name),
-            if let Some(project_index) = self.project_index
-            {
-                format!("/{}", tos::format_index(project_index))
-            }
-            else
-            {
-                String::new()
-            }
-        )
+        format_sections(&self.list_name, &self.project_indexes)
     }
 }
@@ -1,20 +1,191 @@
+mod capture;
 mod commands;
+mod completion;
+mod config;
+mod dates;
 mod dirs;
+mod editor;
 mod file;
 mod gtd;
 mod indexer;
 mod itempath;
+mod man;
+mod markdown;
+mod process;
+mod prompt;
+mod recurrence;
+mod review;
+mod shell;
 mod text;
 pub mod tos;
+mod tui;
 
 use std::error::Error;
+use std::io;
+use std::io::IsTerminal;
+use std::path::PathBuf;
 
+use clap::Args as ClapArgs;
+use clap::CommandFactory;
 use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
+use clap_complete::ArgValueCandidates;
+use clap_complete::CompleteEnv;
+use gtd::Energy;
+use gtd::ListKind;
+use gtd::Priority;
+use gtd::Project;
 use gtd::Status;
+use gtd::Task;
+use recurrence::Recurrence;
+use serde::Deserialize;
+use serde::Serialize;
 
 pub type EResult<T> = Result<T, Box<dyn Error>>;
 
+/// The order in which projects and tasks are shown
+#[derive(ValueEnum, Serialize, Deserialize, Copy, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder
+{
+    /// Keep the order in which items were added
+    #[default]
+    Index,
+    /// Show the highest priority items first
+    Priority,
+}
+
+/// The set of colours output is printed with
+#[derive(
+    ValueEnum, Serialize, Deserialize, Copy, Clone, Default, PartialEq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme
+{
+    /// Bright colours for dark terminals
+    #[default]
+    Dark,
+    /// Darker colours for light terminals
+    Light,
+    /// Bright, saturated colours without any greys
+    HighContrast,
+    /// No colours at all
+    Monochrome,
+}
+
+/// When to print colours
+#[derive(ValueEnum, Copy, Clone, Default)]
+pub enum ColorChoice
+{
+    /// Only when printing to a terminal, unless NO_COLOR or CLICOLOR_FORCE
+    /// say otherwise
+    #[default]
+    Auto,
+    /// Always
+    Always,
+    /// Never
+    Never,
+}
+
+/// The kind of item a path points to
+#[derive(ValueEnum, Copy, Clone, Default)]
+pub enum ItemKind
+{
+    #[default]
+    Task,
+    Project,
+    List,
+}
+
+#[derive(ClapArgs)]
+pub struct TaskEditArgs
+{
+    /// The path to the task to be edited
+    #[arg(add = ArgValueCandidates::new(completion::tasks))]
+    path: String,
+    /// The task's new title
+    #[arg(long)]
+    name: Option<String>,
+    /// The task's new description ("none" to remove it)
+    #[arg(long)]
+    description: Option<String>,
+    /// The task's new priority: A, B or C ("none" to remove it)
+    #[arg(long, short)]
+    priority: Option<String>,
+    /// The task's new due date ("none" to remove it)
+    #[arg(long)]
+    due: Option<String>,
+    /// The day the task resurfaces in the inbox if it is in a someday list
+    /// ("none" to remove it)
+    #[arg(long)]
+    tickler: Option<String>,
+    /// The person the task was handed over to ("none" to remove it)
+    #[arg(long)]
+    delegate: Option<String>,
+    /// The day to check on the delegate's progress ("none" to remove it)
+    #[arg(long)]
+    follow_up: Option<String>,
+    /// The task's new recurrence rule ("none" to remove it)
+    #[arg(long)]
+    recur: Option<String>,
+    /// The task's new time estimate, e.g. 15m ("none" to remove it)
+    #[arg(long, short = 't')]
+    estimate: Option<String>,
+    /// The task's new energy level: low, medium or high ("none" to remove
+    /// it)
+    #[arg(long)]
+    energy: Option<String>,
+    /// The task's new contexts, e.g. @home ("none" to remove them)
+    #[arg(long = "context", short)]
+    context: Vec<String>,
+}
+
+#[derive(ClapArgs)]
+pub struct ProjectEditArgs
+{
+    /// The path of the project to be edited
+    #[arg(add = ArgValueCandidates::new(completion::projects))]
+    path: String,
+    /// The project's new name
+    #[arg(long)]
+    name: Option<String>,
+    /// The project's new priority: A, B or C ("none" to remove it)
+    #[arg(long, short)]
+    priority: Option<String>,
+    /// Make the project's tasks be done in order
+    #[arg(long, short, conflicts_with = "parallel")]
+    sequential: bool,
+    /// Make the project's tasks doable in any order
+    #[arg(long)]
+    parallel: bool,
+    /// The area of focus the project belongs to ("none" to remove it)
+    #[arg(long, add = ArgValueCandidates::new(completion::areas))]
+    area: Option<String>,
+    /// The goal the project works towards ("none" to remove it)
+    #[arg(long, add = ArgValueCandidates::new(completion::goals))]
+    goal: Option<String>,
+    /// What done looks like for the project ("none" to remove it)
+    #[arg(long)]
+    outcome: Option<String>,
+    /// The day the project resurfaces in the inbox if it is in a someday
+    /// list ("none" to remove it)
+    #[arg(long)]
+    tickler: Option<String>,
+    /// The project's new notes ("none" to remove them)
+    #[arg(long, conflicts_with = "editor")]
+    notes: Option<String>,
+    /// Edit the project's notes in $EDITOR
+    #[arg(long, short)]
+    editor: bool,
+    /// A link or file path to keep as reference material (repeatable)
+    #[arg(long = "add-reference", short = 'r')]
+    add_references: Vec<String>,
+    /// The index of a reference to be removed (repeatable)
+    #[arg(long = "remove-reference")]
+    remove_references: Vec<String>,
+}
+
 /// Commands to deal with projects
 #[derive(Subcommand)]
 pub enum ProjectSubcommand
@@ -22,31 +193,63 @@ pub enum ProjectSubcommand
     /// Create a project
     Create
     {
-        /// The path to create the project at
+        /// The path to the list or project to create the project at
+        #[arg(add = ArgValueCandidates::new(completion::containers))]
         path: String,
         name: String,
+        /// The project's priority: A, B or C
+        #[arg(long, short)]
+        priority: Option<String>,
+        /// If provided, the project's tasks must be done in order
+        #[arg(long, short)]
+        sequential: bool,
+        /// The area of focus the project belongs to
+        #[arg(long, add = ArgValueCandidates::new(completion::areas))]
+        area: Option<String>,
+        /// The goal the project works towards
+        #[arg(long, add = ArgValueCandidates::new(completion::goals))]
+        goal: Option<String>,
+        /// What done looks like for the project
+        #[arg(long)]
+        outcome: Option<String>,
     },
 
     /// Remove a project
     Remove
     {
         /// The path of the project to be removed
+        #[arg(add = ArgValueCandidates::new(completion::projects))]
         path: String,
     },
     /// Move a project
     Move
     {
         /// The current location of the project
+        #[arg(add = ArgValueCandidates::new(completion::projects))]
         source: String,
         /// The final location of the project
+        #[arg(add = ArgValueCandidates::new(completion::containers))]
         destination: String,
     },
     /// Show the contents of a project
     Show
     {
         /// The path to the list to be shown
+        #[arg(add = ArgValueCandidates::new(completion::projects))]
         path: String,
+        /// The order to show the project's tasks in (default: the `sort`
+        /// setting)
+        #[arg(long, value_enum)]
+        sort: Option<SortOrder>,
+        /// If provided, only show the items with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// If provided, show the description of each task under it
+        #[arg(long, short)]
+        verbose: bool,
     },
+    /// Change the details of a project
+    Edit(ProjectEditArgs),
 }
 
 /// Commands to deal with lists
@@ -58,12 +261,31 @@ pub enum ListSubcommand
     {
         /// The new list's name
         name: String,
+        /// The role of the list: inbox, next-actions, waiting-for, someday,
+        /// reference or done
+        #[arg(long, short)]
+        kind: Option<String>,
+    },
+
+    /// Change the details of a list
+    Edit
+    {
+        /// The name of the list to be edited
+        #[arg(add = ArgValueCandidates::new(completion::lists))]
+        list: String,
+        /// The list's new name
+        #[arg(long)]
+        name: Option<String>,
+        /// The list's new role ("none" to remove it)
+        #[arg(long, short)]
+        kind: Option<String>,
     },
 
     /// Remove a list
     Remove
     {
         /// The name of the list to be removed
+        #[arg(add = ArgValueCandidates::new(completion::lists))]
         list: String,
     },
 
@@ -71,10 +293,67 @@ pub enum ListSubcommand
     Show
     {
         /// The name of the list to show the contents of
+        #[arg(add = ArgValueCandidates::new(completion::lists))]
         list: String,
         /// If provided, lists project's tasks as well
         #[arg(long, short)]
         all: bool,
+        /// The order to show the list's projects and tasks in (default: the
+        /// `sort` setting)
+        #[arg(long, value_enum)]
+        sort: Option<SortOrder>,
+        /// If provided, only show the items with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// If provided, show the description of each task under it
+        #[arg(long, short)]
+        verbose: bool,
+    },
+}
+
+/// Commands to deal with the checklist of a task
+#[derive(Subcommand)]
+pub enum ChecklistSubcommand
+{
+    /// Add items to the end of a task's checklist
+    Add
+    {
+        /// The path to the task
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The names of the items to be added
+        #[arg(required = true)]
+        items: Vec<String>,
+    },
+
+    /// Tick an item of a task's checklist
+    Tick
+    {
+        /// The path to the task
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The index of the item to be ticked
+        index: String,
+    },
+
+    /// Untick an item of a task's checklist
+    Untick
+    {
+        /// The path to the task
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The index of the item to be unticked
+        index: String,
+    },
+
+    /// Remove an item from a task's checklist
+    Remove
+    {
+        /// The path to the task
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The index of the item to be removed
+        index: String,
     },
 }
 
@@ -86,17 +365,41 @@ pub enum TaskSubcommand
     Create
     {
         /// The path to create the task at
+        #[arg(add = ArgValueCandidates::new(completion::containers))]
         path: String,
         /// The task's title
         name: String,
         /// The task's description
         description: Option<String>,
+        /// The task's due date, e.g. 2026-10-23, fri or "in 3 days"
+        #[arg(long)]
+        due: Option<String>,
+        /// How often the task repeats (e.g. "weekly", "every 3 days",
+        /// "every mon,thu", "2 days after completion")
+        #[arg(long)]
+        recur: Option<String>,
+        /// The task's priority: A, B or C
+        #[arg(long, short)]
+        priority: Option<String>,
+        /// The time needed to do the task, e.g. 15m or 1h30m
+        #[arg(long, short = 't')]
+        estimate: Option<String>,
+        /// The energy needed to do the task: low, medium or high
+        #[arg(long)]
+        energy: Option<String>,
+        /// A context the task can be done in, e.g. @home (repeatable)
+        #[arg(long = "context", short)]
+        contexts: Vec<String>,
     },
 
+    /// Change the details of a task
+    Edit(TaskEditArgs),
+
     /// Remove a task
     Remove
     {
         /// The path of the task to be deleted
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
         path: String,
     },
 
@@ -104,8 +407,10 @@ pub enum TaskSubcommand
     Move
     {
         /// The path to the current location of the task
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
         source: String,
         /// The path to the final location of the task
+        #[arg(add = ArgValueCandidates::new(completion::containers))]
         destination: String,
     },
 
@@ -113,9 +418,159 @@ pub enum TaskSubcommand
     Mark
     {
         /// The path to the task to be modified
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
         path: String,
         /// The new status for the task (default: DONE)
         new_status: Option<String>,
+        /// Mark the task as done even if it is waiting on other tasks
+        #[arg(long, short)]
+        force: bool,
+    },
+
+    /// Make a task wait on another task
+    Depend
+    {
+        /// The path to the task that has to wait
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The path to the task that has to be done first
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        blocker: String,
+    },
+
+    /// Stop a task from waiting on another task
+    Undepend
+    {
+        /// The path to the task that is waiting
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The path to the task it is waiting on
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        blocker: String,
+    },
+
+    /// Deal with the small steps of a task
+    Checklist
+    {
+        #[command(subcommand)]
+        sub: ChecklistSubcommand,
+    },
+}
+
+/// Commands to deal with areas of focus
+#[derive(Subcommand)]
+pub enum AreaSubcommand
+{
+    /// Create an area of focus
+    Create
+    {
+        /// The new area's name
+        name: String,
+        /// What keeping the area up to standard means
+        description: Option<String>,
+    },
+
+    /// Remove an area of focus, unlinking its goals and projects
+    Remove
+    {
+        /// The name of the area to be removed
+        #[arg(add = ArgValueCandidates::new(completion::areas))]
+        name: String,
+    },
+}
+
+/// Commands to deal with goals
+#[derive(Subcommand)]
+pub enum GoalSubcommand
+{
+    /// Create a goal
+    Create
+    {
+        /// The new goal's name
+        name: String,
+        /// The area of focus the goal belongs to
+        #[arg(long, add = ArgValueCandidates::new(completion::areas))]
+        area: Option<String>,
+        /// The date the goal should be achieved by, e.g. "end of year"
+        #[arg(long)]
+        due: Option<String>,
+    },
+
+    /// Remove a goal, unlinking its projects
+    Remove
+    {
+        /// The name of the goal to be removed
+        #[arg(add = ArgValueCandidates::new(completion::goals))]
+        name: String,
+    },
+}
+
+/// Commands to deal with tags
+#[derive(Subcommand)]
+pub enum TagSubcommand
+{
+    /// Add a tag to one or more items
+    Add
+    {
+        /// The tag to be added, e.g. #q4
+        tag: String,
+        /// The paths to the items to be tagged
+        #[arg(required = true, add = ArgValueCandidates::new(completion::items))]
+        paths: Vec<String>,
+        /// The kind of item the paths point to
+        #[arg(long, short, value_enum, default_value_t)]
+        kind: ItemKind,
+    },
+
+    /// Remove a tag from one or more items
+    Remove
+    {
+        /// The tag to be removed
+        tag: String,
+        /// The paths to the items to be untagged
+        #[arg(required = true, add = ArgValueCandidates::new(completion::items))]
+        paths: Vec<String>,
+        /// The kind of item the paths point to
+        #[arg(long, short, value_enum, default_value_t)]
+        kind: ItemKind,
+    },
+
+    /// Rename a tag on every item of the workspace
+    Rename
+    {
+        /// The current name of the tag
+        tag: String,
+        /// The new name of the tag
+        new_name: String,
+    },
+}
+
+/// Commands to deal with the configuration
+#[derive(Subcommand)]
+pub enum ConfigSubcommand
+{
+    /// Show every setting, its value and where it was set
+    List,
+
+    /// Show the value of a setting
+    Get
+    {
+        /// The setting, e.g. date-format or colors.title
+        #[arg(add = ArgValueCandidates::new(completion::settings))]
+        key: String,
+    },
+
+    /// Change a setting for every workspace ("none" to remove it)
+    Set
+    {
+        /// The setting, e.g. date-format or colors.title
+        #[arg(add = ArgValueCandidates::new(completion::settings))]
+        key: String,
+        /// The setting's new value
+        value: String,
+        /// Change the setting for the current workspace only
+        #[arg(long, short)]
+        workspace: bool,
     },
 }
 
@@ -140,6 +595,110 @@ pub enum GTDSubcommand
         sub: ProjectSubcommand,
     },
 
+    Tag
+    {
+        #[command(subcommand)]
+        sub: TagSubcommand,
+    },
+
+    Area
+    {
+        #[command(subcommand)]
+        sub: AreaSubcommand,
+    },
+
+    Goal
+    {
+        #[command(subcommand)]
+        sub: GoalSubcommand,
+    },
+
+    /// Show every area of focus and goal with their active projects
+    Areas,
+
+    /// Hand a task over to someone else, moving it to the waiting-for list
+    Delegate
+    {
+        /// The path to the task to be delegated
+        #[arg(add = ArgValueCandidates::new(completion::tasks))]
+        path: String,
+        /// The person the task is handed over to
+        person: String,
+        /// The day to check on the person's progress, e.g. fri or 1w
+        #[arg(long, short)]
+        follow_up: Option<String>,
+    },
+
+    /// Quickly capture one or more tasks, in the inbox by default
+    ///
+    /// Without items, one item is read from each line of the standard
+    /// input. Items can use shorthand: `@context`, `+project`, `due:date`,
+    /// `!priority` and `#tag`, e.g. "call bob @phone +website !high".
+    #[command(visible_alias = "in")]
+    Add
+    {
+        /// The items to be captured
+        items: Vec<String>,
+        /// The list to capture the items at (default: the inbox)
+        #[arg(long, short, add = ArgValueCandidates::new(completion::lists))]
+        list: Option<String>,
+        /// Take the items as they are, without parsing shorthand
+        #[arg(long, short)]
+        raw: bool,
+    },
+
+    /// Walk through each task of the inbox, deciding what to do with it
+    Process,
+
+    /// Step through the weekly review checklist
+    Review
+    {
+        /// How many days ahead to look for due tasks
+        #[arg(long, short, default_value_t = 14)]
+        days: i64,
+    },
+
+    /// Show the delegated tasks grouped by the person they were handed over
+    /// to, highlighting overdue follow-ups
+    Waiting,
+
+    /// Browse and edit the workspace in a full-screen terminal interface
+    Tui,
+
+    /// Type subcommands one after the other, with history and completion,
+    /// without reloading the workspace for each of them
+    Shell,
+
+    /// Print the script that sets up the completion of subcommands, list
+    /// names and item paths for a shell
+    ///
+    /// For bash, add `source <(vgtd completions bash)` to ~/.bashrc; for zsh,
+    /// `source <(vgtd completions zsh)` to ~/.zshrc; and for fish,
+    /// `vgtd completions fish | source` to ~/.config/fish/config.fish.
+    Completions
+    {
+        /// The shell to set up the completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the manual page of vgtd, e.g. `vgtd man | man -l -`
+    Man
+    {
+        /// Write the pages of vgtd and of every subcommand to this directory
+        /// instead
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show or change the settings, kept in the configuration directory
+    /// and overridden by the .gtd.config.toml file of the workspace
+    Config
+    {
+        #[command(subcommand)]
+        sub: ConfigSubcommand,
+    },
+
     /// Initialize a new workspace (create .gtd.toml file)
     Init,
 
@@ -148,6 +707,28 @@ pub enum GTDSubcommand
 
     /// Show all the lists in the workspace
     Lists,
+
+    /// Show all the tags in the workspace, with the amount of items that
+    /// have each of them
+    Tags,
+
+    /// Show the available actions of every list and project, and the
+    /// projects that are stalled
+    ///
+    /// Only the first TODO task of a sequential project is available. The
+    /// actions can be filtered by the time, energy and context at hand.
+    Next
+    {
+        /// The time available, e.g. 15m or 1h
+        #[arg(long, short)]
+        time: Option<String>,
+        /// The energy available: low, medium or high
+        #[arg(long, short)]
+        energy: Option<String>,
+        /// The context you are in, e.g. @home
+        #[arg(long, short)]
+        context: Option<String>,
+    },
 }
 
 #[derive(Parser)]
@@ -160,14 +741,104 @@ pub struct Args
     /// If provided, initialize the global workspace
     #[arg(long, short)]
     global: bool,
+
+    /// When to print colours
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t)]
+    color: ColorChoice,
+
+    /// Print long output straight to the terminal instead of through the
+    /// pager
+    #[arg(long)]
+    no_pager: bool,
+}
+
+/// Read the non-empty lines of the standard input, unless it is a terminal
+fn read_stdin_lines() -> EResult<Vec<String>>
+{
+    if io::stdin().is_terminal()
+    {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Nothing to capture.",
+        )));
+    }
+
+    let lines = io::stdin()
+        .lines()
+        .collect::<Result<Vec<String>, io::Error>>()?;
+
+    Ok(lines
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .collect())
+}
+
+/// Whether `sub` only shows the workspace or walks through it interactively,
+/// so that tickled items can resurface in the inbox without shifting the
+/// indexes a command was given
+fn resurfaces_tickled(sub: &GTDSubcommand) -> bool
+{
+    matches!(
+        sub,
+        GTDSubcommand::List {
+            sub: ListSubcommand::Show { .. }
+        } | GTDSubcommand::Project {
+            sub: ProjectSubcommand::Show { .. }
+        } | GTDSubcommand::Lists
+            | GTDSubcommand::Tags
+            | GTDSubcommand::Areas
+            | GTDSubcommand::Waiting
+            | GTDSubcommand::Next { .. }
+            | GTDSubcommand::Process
+            | GTDSubcommand::Review { .. }
+            | GTDSubcommand::Tui
+            | GTDSubcommand::Shell
+    )
 }
 
 pub fn parse_cli_arguments() -> EResult<()>
 {
+    CompleteEnv::with_factory(Args::command)
+        .var(completion::COMPLETE_VAR)
+        .complete();
+
     let args = Args::parse();
 
+    if let GTDSubcommand::Completions { shell } = args.sub
+    {
+        return completion::write_registration(shell);
+    }
+
+    if let GTDSubcommand::Man { output } = &args.sub
+    {
+        return man::generate(Args::command(), output.as_deref());
+    }
+
+    tos::use_colors(args.color);
+
     let file_path = dirs::get_workspace_file_path(args.global)?;
 
+    if let GTDSubcommand::Config { sub } = &args.sub
+    {
+        return match sub
+        {
+            ConfigSubcommand::List => config::show_settings(&file_path),
+            ConfigSubcommand::Get { key } =>
+            {
+                config::show_setting(&file_path, key)
+            }
+            ConfigSubcommand::Set {
+                key,
+                value,
+                workspace,
+            } => config::change_setting(&file_path, key, value, *workspace),
+        };
+    }
+
+    config::load(&file_path)?;
+
+    tos::use_pager(!args.no_pager && config::get().pager());
+
     if let GTDSubcommand::Init = args.sub
     {
         return commands::initialize_workspace(&file_path);
@@ -180,7 +851,25 @@ pub fn parse_cli_arguments() -> EResult<()>
 
     let mut file = file::parse(&file_path)?;
 
-    match args.sub
+    if resurfaces_tickled(&args.sub)
+    {
+        commands::resurface_tickled_items(&mut file)?;
+    }
+
+    run_subcommand(&mut file, &file_path, args.sub)?;
+
+    file.write_to_file(&file_path)
+}
+
+/// Run a subcommand against a workspace that is already loaded, leaving it to
+/// the caller to save it
+fn run_subcommand(
+    file: &mut gtd::File,
+    file_path: &str,
+    sub: GTDSubcommand,
+) -> EResult<()>
+{
+    match sub
     {
         GTDSubcommand::Task { sub } =>
         {
@@ -190,72 +879,296 @@ pub fn parse_cli_arguments() -> EResult<()>
                     path,
                     name,
                     description,
+                    due,
+                    recur,
+                    priority,
+                    estimate,
+                    energy,
+                    contexts,
                 } =>
                 {
-                    commands::create_task(&mut file, path, name, description)?
+                    let mut task = Task::new(name, description);
+                    task.due =
+                        due.as_deref().map(dates::parse_date).transpose()?;
+                    task.recurrence = recur
+                        .as_deref()
+                        .map(str::parse::<Recurrence>)
+                        .transpose()?;
+                    task.priority = priority
+                        .as_deref()
+                        .map(Priority::parse)
+                        .transpose()?;
+                    task.estimate = estimate
+                        .as_deref()
+                        .map(dates::parse_duration)
+                        .transpose()?;
+                    task.energy =
+                        energy.as_deref().map(Energy::parse).transpose()?;
+                    task.contexts = contexts
+                        .iter()
+                        .map(|c| gtd::parse_context(c))
+                        .collect();
+
+                    commands::create_task(file, path, task)?
+                }
+                TaskSubcommand::Edit(args) =>
+                {
+                    commands::edit_task(file, &args)?
                 }
                 TaskSubcommand::Remove { path } =>
                 {
-                    commands::remove_task(&mut file, path)?
+                    commands::remove_task(file, path)?
                 }
                 TaskSubcommand::Move {
                     source,
                     destination,
-                } => commands::move_task(&mut file, &source, &destination)?,
-                TaskSubcommand::Mark { path, new_status } =>
+                } => commands::move_task(file, &source, &destination)?,
+                TaskSubcommand::Mark {
+                    path,
+                    new_status,
+                    force,
+                } =>
                 {
                     commands::mark_task(
-                        &mut file,
+                        file,
                         &path,
                         Status::parse(&new_status)?,
+                        force,
                     )?
                 }
+                TaskSubcommand::Depend { path, blocker } =>
+                {
+                    commands::add_task_dependency(file, &path, &blocker)?
+                }
+                TaskSubcommand::Undepend { path, blocker } =>
+                {
+                    commands::remove_task_dependency(file, &path, &blocker)?
+                }
+                TaskSubcommand::Checklist { sub } =>
+                {
+                    match sub
+                    {
+                        ChecklistSubcommand::Add { path, items } =>
+                        {
+                            commands::add_checklist_items(file, &path, items)?
+                        }
+                        ChecklistSubcommand::Tick { path, index } =>
+                        {
+                            commands::check_checklist_item(
+                                file, &path, &index, true,
+                            )?
+                        }
+                        ChecklistSubcommand::Untick { path, index } =>
+                        {
+                            commands::check_checklist_item(
+                                file, &path, &index, false,
+                            )?
+                        }
+                        ChecklistSubcommand::Remove { path, index } =>
+                        {
+                            commands::remove_checklist_item(
+                                file, &path, &index,
+                            )?
+                        }
+                    }
+                }
             }
         }
         GTDSubcommand::List { sub } =>
         {
             match sub
             {
-                ListSubcommand::Show { list, all } =>
+                ListSubcommand::Show {
+                    list,
+                    all,
+                    sort,
+                    tag,
+                    verbose,
+                } =>
+                {
+                    commands::show_list(
+                        file,
+                        &list,
+                        all,
+                        sort.unwrap_or(config::get().sort()),
+                        tag,
+                        verbose,
+                    )?
+                }
+                ListSubcommand::Create { name, kind } =>
                 {
-                    commands::show_list(&mut file, &list, all)?
+                    commands::create_list(
+                        file,
+                        name,
+                        kind.as_deref().map(ListKind::parse).transpose()?,
+                    )?
                 }
-                ListSubcommand::Create { name } =>
+                ListSubcommand::Edit { list, name, kind } =>
                 {
-                    commands::create_list(&mut file, name)?
+                    commands::edit_list(file, &list, name, &kind)?
                 }
                 ListSubcommand::Remove { list } =>
                 {
-                    commands::remove_list(&mut file, &list)?
+                    commands::remove_list(file, &list)?
+                }
+            }
+        }
+        GTDSubcommand::Lists => commands::show_all_lists(file)?,
+        GTDSubcommand::Tags => commands::show_tags(file)?,
+        GTDSubcommand::Areas => commands::show_areas(file)?,
+        GTDSubcommand::Delegate {
+            path,
+            person,
+            follow_up,
+        } =>
+        {
+            commands::delegate_task(
+                file,
+                &path,
+                &person,
+                follow_up.as_deref().map(dates::parse_date).transpose()?,
+            )?
+        }
+        GTDSubcommand::Waiting => commands::show_waiting_for(file)?,
+        GTDSubcommand::Process => process::process_inbox(file)?,
+        GTDSubcommand::Tui => tui::run(file, file_path)?,
+        GTDSubcommand::Shell => shell::run(file, file_path)?,
+        GTDSubcommand::Add { items, list, raw } =>
+        {
+            let items = if items.is_empty()
+            {
+                read_stdin_lines()?
+            }
+            else
+            {
+                items
+            };
+
+            commands::capture(file, &items, list, raw)?
+        }
+        GTDSubcommand::Review { days } => review::weekly_review(file, days)?,
+        GTDSubcommand::Area { sub } =>
+        {
+            match sub
+            {
+                AreaSubcommand::Create { name, description } =>
+                {
+                    commands::create_area(file, &name, description)?
+                }
+                AreaSubcommand::Remove { name } =>
+                {
+                    commands::remove_area(file, &name)?
                 }
             }
         }
-        GTDSubcommand::Lists => commands::show_all_lists(&mut file)?,
+        GTDSubcommand::Goal { sub } =>
+        {
+            match sub
+            {
+                GoalSubcommand::Create { name, area, due } =>
+                {
+                    commands::create_goal(
+                        file,
+                        &name,
+                        area,
+                        due.as_deref().map(dates::parse_date).transpose()?,
+                    )?
+                }
+                GoalSubcommand::Remove { name } =>
+                {
+                    commands::remove_goal(file, &name)?
+                }
+            }
+        }
+        GTDSubcommand::Tag { sub } =>
+        {
+            match sub
+            {
+                TagSubcommand::Add { tag, paths, kind } =>
+                {
+                    commands::tag_items(file, &tag, &paths, kind, true)?
+                }
+                TagSubcommand::Remove { tag, paths, kind } =>
+                {
+                    commands::tag_items(file, &tag, &paths, kind, false)?
+                }
+                TagSubcommand::Rename { tag, new_name } =>
+                {
+                    commands::rename_tag(file, &tag, &new_name)?
+                }
+            }
+        }
+        GTDSubcommand::Next {
+            time,
+            energy,
+            context,
+        } =>
+        {
+            commands::show_next_actions(
+                file,
+                time.as_deref().map(dates::parse_duration).transpose()?,
+                energy.as_deref().map(Energy::parse).transpose()?,
+                context,
+            )?
+        }
         GTDSubcommand::Project { sub } =>
         {
             match sub
             {
-                ProjectSubcommand::Create { path, name } =>
+                ProjectSubcommand::Create {
+                    path,
+                    name,
+                    priority,
+                    sequential,
+                    area,
+                    goal,
+                    outcome,
+                } =>
                 {
-                    commands::create_project(&mut file, &path, name)?
+                    let mut project = Project::new(name);
+                    project.priority = priority
+                        .as_deref()
+                        .map(Priority::parse)
+                        .transpose()?;
+                    project.sequential = sequential;
+                    project.area = area;
+                    project.goal = goal;
+                    project.outcome = outcome;
+
+                    commands::create_project(file, &path, project)?
                 }
                 ProjectSubcommand::Remove { path } =>
                 {
-                    commands::remove_project(&mut file, &path)?
+                    commands::remove_project(file, &path)?
                 }
                 ProjectSubcommand::Move {
                     source,
                     destination,
-                } => commands::move_project(&mut file, &source, &destination)?,
-                ProjectSubcommand::Show { path } =>
+                } => commands::move_project(file, &source, &destination)?,
+                ProjectSubcommand::Show {
+                    path,
+                    sort,
+                    tag,
+                    verbose,
+                } =>
+                {
+                    commands::show_project(
+                        file,
+                        &path,
+                        sort.unwrap_or(config::get().sort()),
+                        tag,
+                        verbose,
+                    )?
+                }
+                ProjectSubcommand::Edit(args) =>
                 {
-                    commands::show_project(&mut file, &path)?
+                    commands::edit_project(file, &args)?
                 }
             }
         }
         _ =>
         {}
-    };
+    }
 
-    file.write_to_file(&file_path)
+    Ok(())
 }
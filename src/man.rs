@@ -0,0 +1,139 @@
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use clap::Command;
+use clap_mangen::Man;
+
+use crate::tos;
+use crate::EResult;
+
+/// The names of the positional arguments that take item paths or list names
+const PATH_ARGUMENTS: [&str; 6] =
+    ["path", "paths", "source", "destination", "blocker", "list"];
+
+/// The section describing the syntax of the paths parsed by `itempath`
+const ITEM_PATHS_SECTION: &str = r#".SH "ITEM PATHS"
+Projects and tasks are referred to by their path: the name of the list they
+are in, followed by the index of each project leading to them, separated by
+slashes. Indexes start at 1 and are the ones shown by \fBvgtd list show\fR and
+\fBvgtd project show\fR.
+.PP
+The same path can point to a task or to a project: commands that act on tasks
+read the last index as a task index, while commands that act on projects or
+that put items somewhere read it as a project index.
+.TP
+\fBinbox\fR
+The list named "inbox".
+.TP
+\fBinbox/1\fR
+The first task of the "inbox" list, or its first project.
+.TP
+\fBnext/2/3\fR
+The third task of the second project of the "next" list, or its third
+sub\-project.
+.TP
+\fBnext/2/1/4\fR
+The fourth task of the first sub\-project of the second project of the "next"
+list.
+.PP
+Indexes shift as items are added, moved and removed, so show the container
+again after changing it.
+"#;
+
+/// Check whether the command, or any of its subcommands, takes item paths
+fn takes_paths(command: &Command) -> bool
+{
+    command
+        .get_positionals()
+        .any(|argument| PATH_ARGUMENTS.contains(&argument.get_id().as_str()))
+        || command.get_subcommands().any(takes_paths)
+}
+
+/// Render the manual page of a command, adding the item path syntax to the
+/// pages of the commands that take paths
+fn render(command: &Command, output: &mut dyn Write) -> EResult<()>
+{
+    let man = Man::new(command.clone())
+        .source(format!("vgtd {}", env!("CARGO_PKG_VERSION")))
+        .manual("vGTD Manual");
+
+    man.render_title(output)?;
+    man.render_name_section(output)?;
+    man.render_synopsis_section(output)?;
+    man.render_description_section(output)?;
+    man.render_options_section(output)?;
+
+    if command.has_subcommands()
+    {
+        man.render_subcommands_section(output)?;
+    }
+
+    if takes_paths(command)
+    {
+        output.write_all(ITEM_PATHS_SECTION.as_bytes())?;
+    }
+
+    if command.get_version().is_some()
+    {
+        man.render_version_section(output)?;
+    }
+
+    if command.get_author().is_some()
+    {
+        man.render_authors_section(output)?;
+    }
+
+    Ok(())
+}
+
+/// Write the manual page of the command and of each of its subcommands to
+/// `directory`, returning how many pages were written
+fn write_pages(command: &Command, directory: &Path) -> EResult<usize>
+{
+    let name = command
+        .get_display_name()
+        .unwrap_or_else(|| command.get_name());
+
+    let mut page = fs::File::create(directory.join(format!("{}.1", name)))?;
+
+    render(command, &mut page)?;
+
+    let mut written = 1;
+
+    for subcommand in command.get_subcommands()
+    {
+        written += write_pages(subcommand, directory)?;
+    }
+
+    Ok(written)
+}
+
+/// Print the manual page of `vgtd`, or write the pages of every subcommand to
+/// `directory` if one is given
+pub fn generate(command: Command, directory: Option<&Path>) -> EResult<()>
+{
+    let mut command = command.disable_help_subcommand(true);
+
+    command.build();
+
+    match directory
+    {
+        Some(directory) =>
+        {
+            fs::create_dir_all(directory)?;
+
+            let written = write_pages(&command, directory)?;
+
+            tos::send_success(&format!(
+                "{} manual pages written to {}.",
+                tos::format_number(written),
+                directory.display()
+            ));
+        }
+        None => render(&command, &mut io::stdout())?,
+    }
+
+    Ok(())
+}
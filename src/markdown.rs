@@ -0,0 +1,235 @@
+use colored::Colorize;
+
+use crate::tos;
+
+const BULLET: &str = "•";
+const BOLD_MARKERS: [&str; 2] = ["**", "__"];
+
+/// A paragraph or list item, gathered from one or more lines of markdown
+struct Block
+{
+    /// The bullet or number of a list item
+    marker: Option<String>,
+    words: Vec<String>,
+}
+
+/// Split a line of markdown into words, styling the bold and linked parts
+fn styled_words(line: &str) -> Vec<String>
+{
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    let mut bold = false;
+
+    // Add a piece of text to the words, continuing the current word if the
+    // piece doesn't start with a space
+    let mut push = |text: &str, bold: bool, link: bool, word: &mut String| {
+        for (position, piece) in text.split(' ').enumerate()
+        {
+            if position > 0 && !word.is_empty()
+            {
+                words.push(std::mem::take(word));
+            }
+
+            if piece.is_empty()
+            {
+                continue;
+            }
+
+            let mut styled = piece.normal();
+
+            if bold
+            {
+                styled = styled.bold();
+            }
+
+            if link
+            {
+                styled = styled.underline();
+            }
+
+            word.push_str(&styled.to_string());
+        }
+    };
+
+    let mut rest = line;
+
+    while !rest.is_empty()
+    {
+        let next = BOLD_MARKERS
+            .iter()
+            .chain(&["["])
+            .filter_map(|marker| rest.find(marker))
+            .min()
+            .unwrap_or(rest.len());
+
+        push(&rest[..next], bold, false, &mut word);
+        rest = &rest[next..];
+
+        if let Some(marker) =
+            BOLD_MARKERS.iter().find(|marker| rest.starts_with(*marker))
+        {
+            bold = !bold;
+            rest = &rest[marker.len()..];
+        }
+        else if let Some((text, url, after)) = split_link(rest)
+        {
+            push(text, bold, true, &mut word);
+            push(" ", false, false, &mut word);
+            word.push_str(
+                &format!("({})", url).color(tos::palette().link).to_string(),
+            );
+            rest = after;
+        }
+        else if !rest.is_empty()
+        {
+            push("[", bold, false, &mut word);
+            rest = &rest[1..];
+        }
+    }
+
+    if !word.is_empty()
+    {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Split `[text](url)` off the start of `source`, returning the text, the
+/// url and what follows them
+fn split_link(source: &str) -> Option<(&str, &str, &str)>
+{
+    let (text, rest) = source.strip_prefix('[')?.split_once("](")?;
+    let (url, after) = rest.split_once(')')?;
+
+    Some((text, url, after))
+}
+
+/// Split the marker off a list item such as "- milk" or "2. eggs"
+fn split_list_item(line: &str) -> Option<(String, &str)>
+{
+    if let Some(item) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        return Some((BULLET.to_owned(), item));
+    }
+
+    let (number, item) = line.split_once(". ")?;
+
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    Some((format!("{}.", number), item))
+}
+
+/// Wrap `block` to `width` columns, hanging the lines of a list item under
+/// the text that follows its marker
+fn render_block(lines: &mut Vec<String>, block: Block, width: Option<usize>)
+{
+    let Some(marker) = block.marker
+    else
+    {
+        lines.extend(tos::wrap(&block.words, width));
+        return;
+    };
+
+    let hang = " ".repeat(tos::display_width(&marker) + 1);
+    let width = width.map(|width| width.saturating_sub(hang.len()));
+
+    for (position, line) in tos::wrap(&block.words, width).iter().enumerate()
+    {
+        if position == 0
+        {
+            lines.push(format!("{} {}", marker, line));
+        }
+        else
+        {
+            lines.push(format!("{}{}", hang, line));
+        }
+    }
+}
+
+/// Render the paragraphs and lists of `source`, with bold text and links,
+/// wrapped to `width` columns if given
+pub fn render(source: &str, width: Option<usize>) -> Vec<String>
+{
+    let mut lines: Vec<String> = vec![];
+    let mut block: Option<Block> = None;
+
+    for line in source.lines()
+    {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty()
+        {
+            if let Some(block) = block.take()
+            {
+                render_block(&mut lines, block, width);
+                lines.push(String::new());
+            }
+
+            continue;
+        }
+
+        if let Some((marker, item)) = split_list_item(trimmed)
+        {
+            if let Some(block) = block.take()
+            {
+                render_block(&mut lines, block, width);
+            }
+
+            block = Some(Block {
+                marker: Some(marker),
+                words: styled_words(item),
+            });
+
+            continue;
+        }
+
+        // An indented line carries on the list item above it, and any other
+        // line carries on a paragraph
+        let continues = match &block
+        {
+            Some(Block {
+                marker: Some(_), ..
+            }) => line.starts_with(char::is_whitespace),
+            Some(Block { marker: None, .. }) => true,
+            None => false,
+        };
+
+        match &mut block
+        {
+            Some(block) if continues =>
+            {
+                block.words.extend(styled_words(trimmed))
+            }
+            _ =>
+            {
+                if let Some(block) = block.take()
+                {
+                    render_block(&mut lines, block, width);
+                }
+
+                block = Some(Block {
+                    marker: None,
+                    words: styled_words(trimmed),
+                });
+            }
+        }
+    }
+
+    if let Some(block) = block
+    {
+        render_block(&mut lines, block, width);
+    }
+
+    while lines.last().is_some_and(String::is_empty)
+    {
+        lines.pop();
+    }
+
+    lines
+}
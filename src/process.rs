@@ -0,0 +1,280 @@
+use std::io;
+
+use crate::commands;
+use crate::dates;
+use crate::gtd::File;
+use crate::gtd::ListContainer;
+use crate::gtd::ListKind;
+use crate::gtd::Project;
+use crate::gtd::Status;
+use crate::gtd::Task;
+use crate::gtd::TaskContainer;
+use crate::itempath::TaskPath;
+use crate::prompt;
+use crate::tos;
+use crate::EResult;
+
+const CHOICES: [(char, &str); 9] = [
+    ('d', "do it now"),
+    ('g', "delegate"),
+    ('f', "defer"),
+    ('m', "move"),
+    ('p', "make project"),
+    ('t', "trash"),
+    ('s', "someday"),
+    ('k', "skip"),
+    ('q', "quit"),
+];
+
+/// What became of an inbox task after processing it
+enum Outcome
+{
+    /// The task left the inbox
+    Processed,
+    /// The task is still in the inbox
+    Kept,
+    /// The user wants to stop processing
+    Quit,
+}
+
+/// Walk through each task of the inbox, deciding what to do with it
+pub fn process_inbox(file: &mut File) -> EResult<()>
+{
+    let inbox_name = match file.inbox_name()
+    {
+        Some(name) => name,
+        None =>
+        {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::NotFound,
+                "There is no inbox list in the workspace.",
+            )));
+        }
+    };
+
+    let total = file.get_list_forced(&inbox_name)?.tasks().len();
+
+    if total == 0
+    {
+        tos::send_info("The inbox is empty. Nice!");
+
+        return Ok(());
+    }
+
+    let mut index = 0;
+    let mut processed = 0;
+
+    // Only the tasks that were in the inbox to begin with are processed, so
+    // that tasks created along the way (e.g. by recurrence) are left alone
+    for position in 0..total
+    {
+        let task_path = TaskPath {
+            list_name: inbox_name.clone(),
+            project_indexes: vec![],
+            task_index: index,
+        };
+
+        let task = file.get_task(&task_path)?;
+
+        tos::OutputBlock::new()
+            .insert_line(
+                &format!(
+                    "Inbox item {} of {}: {}",
+                    tos::format_number(position + 1),
+                    tos::format_number(total),
+                    tos::format_task(task, &file.open_blockers(task))
+                ),
+                0,
+            )
+            .send();
+
+        match process_task(file, &task_path)?
+        {
+            Outcome::Processed => processed += 1,
+            Outcome::Kept => index += 1,
+            Outcome::Quit => break,
+        }
+    }
+
+    tos::send_success(&format!(
+        "{} of {} inbox items processed.",
+        tos::format_number(processed),
+        tos::format_number(total)
+    ));
+
+    Ok(())
+}
+
+fn process_task(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    loop
+    {
+        let outcome = match prompt::choose("What is it?", &CHOICES)?
+        {
+            'd' => do_now(file, path),
+            'g' => delegate(file, path),
+            'f' => defer(file, path),
+            'm' => move_to_list(file, path),
+            'p' => make_project(file, path),
+            't' => trash(file, path),
+            's' => send_to_someday(file, path),
+            'k' => Ok(Outcome::Kept),
+            _ => Ok(Outcome::Quit),
+        };
+
+        // A mistyped answer shouldn't end the whole processing session
+        match outcome
+        {
+            Ok(outcome) => return Ok(outcome),
+            Err(error) => tos::send_error(&error.to_string()),
+        }
+    }
+}
+
+/// Move the task at `path` to the end of the first list of the given kind
+fn move_to_kind(
+    file: &mut File,
+    path: &TaskPath,
+    kind: ListKind,
+    default_name: &str,
+) -> EResult<()>
+{
+    let list_name = file.list_of_kind_mut(kind, default_name).name.clone();
+
+    commands::move_task(file, &path.to_string(), &list_name)
+}
+
+fn do_now(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    commands::mark_task(file, &path.to_string(), Status::DONE, false)?;
+
+    if file.list_of_kind(ListKind::Done).is_some()
+    {
+        move_to_kind(file, path, ListKind::Done, "done")?;
+
+        return Ok(Outcome::Processed);
+    }
+
+    Ok(Outcome::Kept)
+}
+
+fn delegate(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    let person = prompt::ask("Delegate to:")?;
+
+    if person.is_empty()
+    {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "No person provided.",
+        )));
+    }
+
+    let follow_up = prompt::ask("Follow up on (empty for never):")?;
+
+    let follow_up = if follow_up.is_empty()
+    {
+        None
+    }
+    else
+    {
+        Some(dates::parse_date(&follow_up)?)
+    };
+
+    commands::delegate_task(file, &path.to_string(), &person, follow_up)?;
+
+    Ok(Outcome::Processed)
+}
+
+fn defer(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    let date = dates::parse_date(&prompt::ask("Defer until:")?)?;
+
+    file.get_task_mut(path)?.tickler = Some(date);
+
+    move_to_kind(file, path, ListKind::Someday, "someday")?;
+
+    Ok(Outcome::Processed)
+}
+
+fn move_to_list(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    let names: Vec<String> = file
+        .lists()
+        .iter()
+        .filter(|list| list.name != path.list_name)
+        .map(|list| list.name.clone())
+        .collect();
+
+    let list_name =
+        prompt::ask(&format!("Move to which list ({})?", names.join(", ")))?;
+
+    file.get_list_forced(&list_name.to_lowercase())?;
+
+    commands::move_task(file, &path.to_string(), &list_name.to_lowercase())?;
+
+    Ok(Outcome::Processed)
+}
+
+fn make_project(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    let default_list = file
+        .list_of_kind(ListKind::NextActions)
+        .map_or(path.list_name.clone(), |list| list.name.clone());
+
+    let list_name = prompt::ask(&format!(
+        "Create the project in which list (empty for {})?",
+        default_list
+    ))?;
+
+    let list_name = if list_name.is_empty()
+    {
+        default_list
+    }
+    else
+    {
+        list_name.to_lowercase()
+    };
+
+    file.get_list_forced(&list_name)?;
+
+    let next_action =
+        prompt::ask("What is its next action (empty for none)?")?;
+
+    let task = file.get_task(path)?;
+
+    let mut project = Project::new(task.name.clone());
+    project.priority = task.priority;
+    project.tags = task.tags.clone();
+
+    if !next_action.is_empty()
+    {
+        project.push_task(Task::new(next_action, None));
+    }
+
+    commands::create_project(file, &list_name, project)?;
+
+    file.get_container_mut(&path.container())?
+        .remove_task(path.task_index);
+
+    Ok(Outcome::Processed)
+}
+
+fn trash(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    // Pressing `t` already is the confirmation, so don't ask again
+    let task = file
+        .get_container_mut(&path.container())?
+        .remove_task(path.task_index);
+
+    tos::send_success(&format!("Task {} ({}) removed.", path, task.name));
+
+    Ok(Outcome::Processed)
+}
+
+fn send_to_someday(file: &mut File, path: &TaskPath) -> EResult<Outcome>
+{
+    move_to_kind(file, path, ListKind::Someday, "someday")?;
+
+    Ok(Outcome::Processed)
+}
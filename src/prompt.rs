@@ -0,0 +1,150 @@
+use std::io;
+use std::io::IsTerminal;
+use std::io::Write;
+
+use colored::Colorize;
+use crossterm::event;
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use crossterm::terminal;
+
+use crate::config;
+use crate::tos;
+use crate::EResult;
+
+/// The key returned by `choose` when the user presses Ctrl-C or Escape
+pub const CANCEL_KEY: char = '\x1b';
+
+fn print_question(question: &str) -> EResult<()>
+{
+    print!(
+        "{}{} ",
+        tos::get_padding(0),
+        question.color(tos::palette().title)
+    );
+    io::stdout().flush()?;
+
+    Ok(())
+}
+
+/// Wait for a single key press, without the user having to press Enter
+fn read_key() -> EResult<char>
+{
+    terminal::enable_raw_mode()?;
+
+    let key = loop
+    {
+        match event::read()
+        {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press =>
+            {
+                match key.code
+                {
+                    KeyCode::Char('c')
+                        if key.modifiers.contains(KeyModifiers::CONTROL) =>
+                    {
+                        break Ok(CANCEL_KEY);
+                    }
+                    KeyCode::Char(key) => break Ok(key.to_ascii_lowercase()),
+                    KeyCode::Esc => break Ok(CANCEL_KEY),
+                    _ => continue,
+                }
+            }
+            Ok(_) => continue,
+            Err(error) => break Err(error),
+        }
+    };
+
+    terminal::disable_raw_mode()?;
+
+    Ok(key?)
+}
+
+/// Read the first character of a line, for when the input is not a terminal
+fn read_key_from_line() -> EResult<char>
+{
+    let mut line = String::new();
+
+    if io::stdin().read_line(&mut line)? == 0
+    {
+        return Ok(CANCEL_KEY);
+    }
+
+    Ok(line
+        .trim()
+        .chars()
+        .next()
+        .map_or(' ', |key| key.to_ascii_lowercase()))
+}
+
+/// Ask the user to pick one of the `choices` by pressing its key
+pub fn choose(question: &str, choices: &[(char, &str)]) -> EResult<char>
+{
+    let formatted_choices: Vec<String> = choices
+        .iter()
+        .map(|(key, label)| {
+            format!(
+                "[{}] {}",
+                key.to_string().color(tos::palette().number),
+                label
+            )
+        })
+        .collect();
+
+    println!("{}{}", tos::get_padding(1), formatted_choices.join("  "));
+
+    loop
+    {
+        print_question(question)?;
+
+        let key = if io::stdin().is_terminal()
+        {
+            let key = read_key()?;
+            println!("{}", if key == CANCEL_KEY { ' ' } else { key });
+            key
+        }
+        else
+        {
+            let key = read_key_from_line()?;
+            println!();
+            key
+        };
+
+        if key == CANCEL_KEY
+            || choices.iter().any(|(choice, _)| *choice == key)
+        {
+            return Ok(key);
+        }
+    }
+}
+
+/// Ask the user whether to go on with something that can't be undone,
+/// unless confirmations are turned off or nobody is there to answer
+pub fn confirm(question: &str) -> EResult<bool>
+{
+    if !config::get().confirm() || !io::stdin().is_terminal()
+    {
+        return Ok(true);
+    }
+
+    Ok(choose(question, &[('y', "yes"), ('n', "no")])? == 'y')
+}
+
+/// Ask the user to type an answer, returning it without surrounding spaces
+pub fn ask(question: &str) -> EResult<String>
+{
+    print_question(question)?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    // The answer was not echoed if it didn't come from a terminal
+    if !io::stdin().is_terminal()
+    {
+        println!();
+    }
+
+    Ok(answer.trim().to_owned())
+}
@@ -0,0 +1,275 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use chrono::Datelike;
+use chrono::Days;
+use chrono::Months;
+use chrono::NaiveDate;
+use chrono::Weekday;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Frequency
+{
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency
+{
+    fn parse_unit(unit: &str) -> Option<Self>
+    {
+        match unit
+        {
+            "day" | "days" => Some(Frequency::Daily),
+            "week" | "weeks" => Some(Frequency::Weekly),
+            "month" | "months" => Some(Frequency::Monthly),
+            "year" | "years" => Some(Frequency::Yearly),
+            _ => None,
+        }
+    }
+
+    fn unit(&self) -> &'static str
+    {
+        match self
+        {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        }
+    }
+
+    fn adverb(&self) -> &'static str
+    {
+        match self
+        {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+            Frequency::Yearly => "yearly",
+        }
+    }
+}
+
+/// A rule describing when the next occurrence of a task is due
+///
+/// Rules are stored in the workspace file in their textual form, such as
+/// `weekly`, `every 3 days`, `every mon,thu` or `2 days after completion`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence
+{
+    pub frequency: Frequency,
+    /// The amount of frequency units between two occurrences
+    pub interval: u32,
+    /// The specific weekdays a weekly task happens on (may be empty)
+    pub weekdays: Vec<Weekday>,
+    /// If set, occurrences are counted from the completion date instead of
+    /// the due date
+    pub after_completion: bool,
+}
+
+impl Recurrence
+{
+    /// Get the date of the occurrence following the one at `from`
+    pub fn next_date(&self, from: NaiveDate) -> NaiveDate
+    {
+        match self.frequency
+        {
+            Frequency::Daily => add_days(from, self.interval),
+            Frequency::Weekly if self.weekdays.is_empty() =>
+            {
+                add_days(from, self.interval.saturating_mul(7))
+            }
+            Frequency::Weekly =>
+            {
+                let mut date = add_days(from, 1);
+
+                // The dates stop at `NaiveDate::MAX` rather than overflow
+                while !self.weekdays.contains(&date.weekday())
+                    && date < NaiveDate::MAX
+                {
+                    date = add_days(date, 1);
+                }
+
+                // Crossing into the next week skips the weeks in between
+                if date.weekday().num_days_from_monday()
+                    <= from.weekday().num_days_from_monday()
+                {
+                    date =
+                        add_days(date, (self.interval - 1).saturating_mul(7));
+                }
+
+                date
+            }
+            Frequency::Monthly => add_months(from, self.interval),
+            Frequency::Yearly =>
+            {
+                add_months(from, self.interval.saturating_mul(12))
+            }
+        }
+    }
+}
+
+fn add_days(date: NaiveDate, days: u32) -> NaiveDate
+{
+    date.checked_add_days(Days::new(days.into()))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+fn add_months(date: NaiveDate, months: u32) -> NaiveDate
+{
+    date.checked_add_months(Months::new(months))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+fn parse_weekdays(source: &str) -> Option<Vec<Weekday>>
+{
+    source
+        .split(',')
+        .map(|day| day.trim().parse::<Weekday>().ok())
+        .collect()
+}
+
+fn format_weekday(weekday: &Weekday) -> String
+{
+    weekday.to_string().to_lowercase()
+}
+
+impl FromStr for Recurrence
+{
+    type Err = Box<dyn Error>;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err>
+    {
+        let invalid = || -> Self::Err {
+            Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Could not parse recurrence rule \"{}\".", source),
+            ))
+        };
+
+        let rule = source.trim().to_lowercase().replace(", ", ",");
+
+        let (rule, after_completion) = match rule
+            .strip_suffix("after completion")
+        {
+            Some(rest) => (rest.trim(), true),
+            None => (rule.as_str(), false),
+        };
+
+        let rule = rule.strip_prefix("every ").unwrap_or(rule);
+
+        let words: Vec<&str> = rule.split_whitespace().collect();
+
+        let mut recurrence = Self {
+            frequency: Frequency::Daily,
+            interval: 1,
+            weekdays: vec![],
+            after_completion,
+        };
+
+        match words.as_slice()
+        {
+            ["daily"] => recurrence.frequency = Frequency::Daily,
+            ["weekly"] => recurrence.frequency = Frequency::Weekly,
+            ["monthly"] => recurrence.frequency = Frequency::Monthly,
+            ["yearly"] => recurrence.frequency = Frequency::Yearly,
+            [unit] if Frequency::parse_unit(unit).is_some() =>
+            {
+                recurrence.frequency =
+                    Frequency::parse_unit(unit).ok_or_else(invalid)?;
+            }
+            [days] =>
+            {
+                recurrence.frequency = Frequency::Weekly;
+                recurrence.weekdays =
+                    parse_weekdays(days).ok_or_else(invalid)?;
+            }
+            [interval, unit, rest @ ..] =>
+            {
+                recurrence.interval =
+                    interval.parse::<u32>().map_err(|_| invalid())?;
+                recurrence.frequency =
+                    Frequency::parse_unit(unit).ok_or_else(invalid)?;
+
+                match rest
+                {
+                    [] =>
+                    {}
+                    ["on", days]
+                        if recurrence.frequency == Frequency::Weekly =>
+                    {
+                        recurrence.weekdays =
+                            parse_weekdays(days).ok_or_else(invalid)?;
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(invalid()),
+        }
+
+        if recurrence.interval == 0
+            || (recurrence.after_completion && !recurrence.weekdays.is_empty())
+        {
+            return Err(invalid());
+        }
+
+        Ok(recurrence)
+    }
+}
+
+impl TryFrom<String> for Recurrence
+{
+    type Error = Box<dyn Error>;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> { source.parse() }
+}
+
+impl From<Recurrence> for String
+{
+    fn from(recurrence: Recurrence) -> Self { recurrence.to_string() }
+}
+
+impl fmt::Display for Recurrence
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let unit = self.frequency.unit();
+
+        if self.after_completion
+        {
+            return write!(
+                f,
+                "{} {}{} after completion",
+                self.interval,
+                unit,
+                if self.interval == 1 { "" } else { "s" }
+            );
+        }
+
+        let weekdays = self
+            .weekdays
+            .iter()
+            .map(format_weekday)
+            .collect::<Vec<String>>()
+            .join(",");
+
+        match (self.interval, self.weekdays.is_empty())
+        {
+            (1, true) => write!(f, "{}", self.frequency.adverb()),
+            (1, false) => write!(f, "every {}", weekdays),
+            (interval, true) => write!(f, "every {} {}s", interval, unit),
+            (interval, false) =>
+            {
+                write!(f, "every {} {}s on {}", interval, unit, weekdays)
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use std::io;
+
+use chrono::Duration;
+use chrono::NaiveDate;
+use colored::Colorize;
+
+use crate::commands;
+use crate::dates;
+use crate::gtd::File;
+use crate::gtd::ListContainer;
+use crate::gtd::ListKind;
+use crate::gtd::Priority;
+use crate::gtd::Task;
+use crate::gtd::TaskContainer;
+use crate::itempath::ContainerPath;
+use crate::process;
+use crate::prompt;
+use crate::tos;
+use crate::tos::OutputFormattable;
+use crate::EResult;
+use crate::SortOrder;
+
+const STEP_COUNT: usize = 6;
+
+const CONTINUE_CHOICES: [(char, &str); 2] = [('c', "continue"), ('q', "quit")];
+
+const PROJECT_CHOICES: [(char, &str); 4] = [
+    ('r', "reviewed"),
+    ('a', "add next action"),
+    ('k', "skip"),
+    ('q', "quit"),
+];
+
+fn send_step(number: usize, title: &str)
+{
+    tos::OutputBlock::new()
+        .insert_line(
+            &format!(
+                "Step {} of {}: {}",
+                tos::format_number(number),
+                tos::format_number(STEP_COUNT),
+                tos::format_section_name(title)
+            ),
+            0,
+        )
+        .send();
+}
+
+/// Ask whether to go on with the review, returning false if the user quits
+fn go_on() -> EResult<bool>
+{
+    Ok(prompt::choose("Ready for the next step?", &CONTINUE_CHOICES)? == 'c')
+}
+
+/// Step through the weekly review checklist, looking ahead `days` days for
+/// due tasks
+pub fn weekly_review(file: &mut File, days: i64) -> EResult<()>
+{
+    // Check the look-ahead before the review starts rather than at its end
+    due_horizon(days)?;
+
+    let steps: [fn(&mut File, i64) -> EResult<bool>; STEP_COUNT] = [
+        review_inbox,
+        review_projects,
+        review_areas,
+        review_waiting_for,
+        review_someday,
+        review_due_tasks,
+    ];
+
+    for (number, step) in steps.iter().enumerate()
+    {
+        if !step(file, days)?
+        {
+            tos::send_info("Review stopped. Progress so far was saved.");
+
+            return Ok(());
+        }
+
+        if number < STEP_COUNT - 1 && !go_on()?
+        {
+            tos::send_info("Review stopped. Progress so far was saved.");
+
+            return Ok(());
+        }
+    }
+
+    tos::send_success("Weekly review done. Enjoy your week!");
+
+    Ok(())
+}
+
+fn review_inbox(file: &mut File, _: i64) -> EResult<bool>
+{
+    send_step(1, "get the inbox to zero");
+
+    let count = file
+        .inbox_name()
+        .and_then(|name| file.get_list(&name))
+        .map_or(0, |inbox| inbox.tasks().len());
+
+    if count == 0
+    {
+        tos::send_info("The inbox is empty.");
+
+        return Ok(true);
+    }
+
+    let choice = prompt::choose(
+        &format!("The inbox has {} items. Process them now?", count),
+        &[('p', "process"), ('k', "skip"), ('q', "quit")],
+    )?;
+
+    match choice
+    {
+        'p' =>
+        {
+            process::process_inbox(file)?;
+            Ok(true)
+        }
+        'k' => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+fn review_projects(file: &mut File, _: i64) -> EResult<bool>
+{
+    send_step(2, "review every project");
+
+    let paths: Vec<ContainerPath> = file
+        .active_projects()
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+
+    if paths.is_empty()
+    {
+        tos::send_info("There are no active projects.");
+
+        return Ok(true);
+    }
+
+    let today = dates::today();
+
+    for (position, path) in paths.iter().enumerate()
+    {
+        let project = file.get_project(path)?;
+
+        let mut output = tos::OutputBlock::new();
+
+        output.insert_line(
+            &format!(
+                "Project {} of {}: {} {}{}",
+                tos::format_number(position + 1),
+                tos::format_number(paths.len()),
+                path.tos_format(),
+                tos::format_project(project),
+                match &project.last_reviewed
+                {
+                    Some(date) =>
+                    {
+                        format!(
+                            " (last reviewed {})",
+                            dates::format_date(date)
+                                .color(tos::palette().date)
+                        )
+                    }
+                    None => " (never reviewed)".to_owned(),
+                }
+            ),
+            0,
+        );
+
+        if let Some(outcome) = &project.outcome
+        {
+            output.insert_line(
+                &format!(
+                    "{} {}",
+                    tos::format_section_name("outcome:"),
+                    outcome
+                ),
+                1,
+            );
+        }
+
+        let actions = project.available_tasks();
+        let tasks_by_id = file.task_index();
+
+        if actions.is_empty()
+        {
+            output.insert_line(
+                &format!("{} No next action.", tos::format_stalled()),
+                1,
+            );
+        }
+
+        for (index, task) in actions
+        {
+            output.insert_line(
+                &format!(
+                    "{} {}",
+                    path.task(index).tos_format(),
+                    tos::format_task(task, &tasks_by_id.open_blockers(task))
+                ),
+                1,
+            );
+        }
+
+        output.send();
+
+        match prompt::choose("Is the project on track?", &PROJECT_CHOICES)?
+        {
+            'r' => file.get_project_mut(path)?.last_reviewed = Some(today),
+            'a' =>
+            {
+                let name = prompt::ask("What is the next action?")?;
+
+                let project = file.get_project_mut(path)?;
+
+                if !name.is_empty()
+                {
+                    project.push_task(Task::new(name, None));
+                }
+
+                project.last_reviewed = Some(today);
+            }
+            'k' => continue,
+            _ => return Ok(false),
+        }
+    }
+
+    Ok(true)
+}
+
+fn review_areas(file: &mut File, _: i64) -> EResult<bool>
+{
+    send_step(3, "check the areas of focus");
+
+    let neglected = file.neglected_areas();
+
+    if file.areas.is_empty()
+    {
+        tos::send_info("There are no areas of focus in the workspace.");
+    }
+    else if neglected.is_empty()
+    {
+        tos::send_info("Every area of focus has an active project.");
+    }
+    else
+    {
+        let mut output = tos::OutputBlock::new();
+
+        output.insert_line("Areas of focus without active projects", 0);
+
+        for area in neglected
+        {
+            output.insert_line(
+                &format!("• {}", tos::format_area_name(&area.name)),
+                1,
+            );
+        }
+
+        output.send();
+    }
+
+    Ok(true)
+}
+
+fn review_waiting_for(file: &mut File, _: i64) -> EResult<bool>
+{
+    send_step(4, "chase what you are waiting for");
+
+    commands::show_waiting_for(file)?;
+
+    Ok(true)
+}
+
+fn review_someday(file: &mut File, _: i64) -> EResult<bool>
+{
+    send_step(5, "look through someday/maybe");
+
+    let names: Vec<String> = file
+        .lists()
+        .iter()
+        .filter(|list| list.kind == Some(ListKind::Someday))
+        .map(|list| list.name.clone())
+        .collect();
+
+    if names.is_empty()
+    {
+        tos::send_info("There are no someday/maybe lists in the workspace.");
+    }
+
+    for name in names
+    {
+        commands::show_list(file, &name, true, SortOrder::Index, None, false)?;
+    }
+
+    Ok(true)
+}
+
+/// The last day of a look-ahead of `days` days from today
+fn due_horizon(days: i64) -> EResult<NaiveDate>
+{
+    let horizon = Duration::try_days(days)
+        .and_then(|duration| dates::today().checked_add_signed(duration));
+
+    match horizon
+    {
+        Some(horizon) => Ok(horizon),
+        None =>
+        {
+            Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Can not look {} days ahead.", days),
+            )))
+        }
+    }
+}
+
+fn review_due_tasks(file: &mut File, days: i64) -> EResult<bool>
+{
+    send_step(6, "look at what is coming up");
+
+    let horizon = due_horizon(days)?;
+
+    let mut tasks: Vec<_> = file
+        .all_tasks()
+        .into_iter()
+        .filter(|(_, task)| {
+            !task.done() && task.due.is_some_and(|due| due <= horizon)
+        })
+        .collect();
+
+    if tasks.is_empty()
+    {
+        tos::send_info(&format!(
+            "Nothing is due in the next {} days.",
+            tos::format_number(days)
+        ));
+
+        return Ok(true);
+    }
+
+    tasks.sort_by_key(|(_, task)| {
+        (task.due, Priority::sort_key(task.priority))
+    });
+
+    let mut output = tos::OutputBlock::new();
+
+    output.insert_line(
+        &format!(
+            "Due in the next {} days (or overdue)",
+            tos::format_number(days)
+        ),
+        0,
+    );
+
+    let tasks_by_id = file.task_index();
+
+    for (path, task) in tasks
+    {
+        output.insert_line(
+            &format!(
+                "{} {}",
+                path.tos_format(),
+                tos::format_task(task, &tasks_by_id.open_blockers(task))
+            ),
+            1,
+        );
+    }
+
+    output.send();
+
+    Ok(true)
+}
@@ -0,0 +1,307 @@
+use std::fs;
+use std::io;
+
+use clap::CommandFactory;
+use clap::Parser;
+use rustyline::completion::Completer;
+use rustyline::completion::Pair;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::CompletionType;
+use rustyline::Config;
+use rustyline::Context;
+use rustyline::Editor;
+use rustyline::Helper;
+
+use crate::dirs;
+use crate::gtd::File;
+use crate::gtd::ListContainer;
+use crate::tos;
+use crate::EResult;
+use crate::GTDSubcommand;
+
+const PROMPT: &str = "vgtd> ";
+
+/// A line typed into the shell: any subcommand of `vgtd`, without the
+/// program name
+#[derive(Parser)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+struct ShellLine
+{
+    #[command(subcommand)]
+    sub: GTDSubcommand,
+}
+
+/// Completes subcommand names, option names, and the names of the lists and
+/// the paths of the items of the workspace
+struct ShellHelper
+{
+    command: clap::Command,
+    /// Each list name and item path, along with the name of the item
+    paths: Vec<(String, String)>,
+}
+
+impl ShellHelper
+{
+    fn new() -> Self
+    {
+        ShellHelper {
+            command: ShellLine::command(),
+            paths: vec![],
+        }
+    }
+
+    fn update_paths(&mut self, file: &File)
+    {
+        self.paths = file
+            .lists()
+            .iter()
+            .map(|list| (list.name.clone(), String::new()))
+            .chain(file.all_projects().into_iter().map(|(path, project)| {
+                (path.to_string(), project.name.clone())
+            }))
+            .chain(
+                file.all_tasks()
+                    .into_iter()
+                    .map(|(path, task)| (path.to_string(), task.name.clone())),
+            )
+            .collect();
+    }
+
+    fn candidates(&self, words: &[&str], word: &str) -> Vec<Pair>
+    {
+        let mut command = &self.command;
+
+        for previous in words
+        {
+            match command.find_subcommand(previous)
+            {
+                Some(subcommand) => command = subcommand,
+                None => break,
+            }
+        }
+
+        if word.starts_with('-')
+        {
+            return command
+                .get_arguments()
+                .filter_map(|argument| argument.get_long())
+                .map(|long| format!("--{}", long))
+                .filter(|long| long.starts_with(word))
+                .map(|long| {
+                    Pair {
+                        display: long.clone(),
+                        replacement: format!("{} ", long),
+                    }
+                })
+                .collect();
+        }
+
+        if command.has_subcommands()
+        {
+            return command
+                .get_subcommands()
+                .flat_map(|subcommand| {
+                    std::iter::once(subcommand.get_name())
+                        .chain(subcommand.get_visible_aliases())
+                })
+                .filter(|name| name.starts_with(word))
+                .map(|name| {
+                    Pair {
+                        display: name.to_string(),
+                        replacement: format!("{} ", name),
+                    }
+                })
+                .collect();
+        }
+
+        self.paths
+            .iter()
+            .filter(|(path, _)| path.starts_with(word))
+            .map(|(path, name)| {
+                Pair {
+                    display: if name.is_empty()
+                    {
+                        path.clone()
+                    }
+                    else
+                    {
+                        format!("{} ({})", path, name)
+                    },
+                    replacement: path.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Completer for ShellHelper
+{
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _context: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)>
+    {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let words: Vec<&str> = line[..start].split_whitespace().collect();
+
+        Ok((start, self.candidates(&words, &line[start..])))
+    }
+}
+
+impl Hinter for ShellHelper
+{
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Refuse the subcommands that make no sense inside the shell
+fn check_available(sub: &GTDSubcommand) -> EResult<()>
+{
+    let reason = match sub
+    {
+        GTDSubcommand::Init | GTDSubcommand::Reset =>
+        {
+            "Workspaces can not be created or reset from the shell."
+        }
+        GTDSubcommand::Shell => "The shell is already running.",
+        GTDSubcommand::Completions { .. } | GTDSubcommand::Man { .. } =>
+        {
+            "Completions and manual pages are generated outside the shell."
+        }
+        GTDSubcommand::Config { .. } =>
+        {
+            "The configuration is changed outside the shell."
+        }
+        GTDSubcommand::Add { items, .. } if items.is_empty() =>
+        {
+            "Please specify the items to capture."
+        }
+        _ => return Ok(()),
+    };
+
+    Err(Box::new(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        reason,
+    )))
+}
+
+/// Run a single line typed into the shell, saving the workspace if it
+/// changed and undoing the changes of a command that failed halfway
+fn run_line(file: &mut File, file_path: &str, line: &str) -> EResult<()>
+{
+    let words = shell_words::split(line)?;
+
+    let sub = match ShellLine::try_parse_from(words)
+    {
+        Ok(parsed) => parsed.sub,
+        Err(error) =>
+        {
+            error.print()?;
+            return Ok(());
+        }
+    };
+
+    check_available(&sub)?;
+
+    let before = toml::to_string(file)?;
+
+    if let Err(error) = crate::run_subcommand(file, file_path, sub)
+    {
+        *file = toml::from_str(&before)?;
+        return Err(error);
+    }
+
+    if toml::to_string(file)? != before
+    {
+        file.write_to_file(file_path)?;
+    }
+
+    Ok(())
+}
+
+/// Read and run subcommands until the user exits, keeping the workspace in
+/// memory between them
+pub fn run(file: &mut File, file_path: &str) -> EResult<()>
+{
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+
+    let mut editor: Editor<ShellHelper, DefaultHistory> =
+        Editor::with_config(config)?;
+    let mut helper = ShellHelper::new();
+
+    helper.update_paths(file);
+    editor.set_helper(Some(helper));
+
+    let history_path = dirs::get_history_file_path();
+
+    if let Some(path) = &history_path
+    {
+        // There is no history yet the first time the shell is run
+        let _ = editor.load_history(path);
+    }
+
+    tos::send_info("Type a subcommand, \"help\" to list them, or \"exit\".");
+
+    loop
+    {
+        let line = match editor.readline(PROMPT)
+        {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(Box::new(error)),
+        };
+
+        let line = line.trim();
+
+        if line.is_empty()
+        {
+            continue;
+        }
+
+        editor.add_history_entry(line)?;
+
+        if line == "exit" || line == "quit"
+        {
+            break;
+        }
+
+        if let Err(error) = run_line(file, file_path, line)
+        {
+            tos::send_error(&error.to_string());
+        }
+
+        if let Some(helper) = editor.helper_mut()
+        {
+            helper.update_paths(file);
+        }
+    }
+
+    if let Some(path) = &history_path
+    {
+        if let Some(parent) = path.parent()
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        editor.save_history(path)?;
+    }
+
+    Ok(())
+}
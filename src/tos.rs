@@ -1,36 +1,581 @@
 // Terminal Output System
 
+use std::env;
+use std::io;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::OnceLock;
+
+use chrono::NaiveDate;
+
+use crate::dates;
+use crate::gtd::ChecklistItem;
+use crate::gtd::Energy;
+use crate::gtd::Goal;
+use crate::gtd::ListKind;
+use crate::gtd::Priority;
+use crate::gtd::Project;
 use crate::gtd::Status;
 use crate::gtd::Task;
 use crate::indexer;
+use crate::markdown;
+use crate::recurrence::Recurrence;
 use crate::text::Formattable;
+use crate::ColorChoice;
+use crate::EResult;
+use crate::Theme;
 
 use colored::Color;
 use colored::Colorize;
+use crossterm::terminal;
+use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
 
 const PADDING_CHAR: char = ' ';
 const BASE_PADDING_LEVEL: usize = 2;
 const BLOCK_PREFIX: &str = "\n";
 const BLOCK_POSTFIX: &str = "\n";
 const PREFIX: &str = "[vGTD]";
-pub const COLOR_PREFIX: Color = Color::BrightGreen;
-pub const COLOR_SUCCESS: Color = Color::BrightGreen;
-pub const COLOR_ERROR: Color = Color::BrightRed;
-pub const COLOR_INFO: Color = Color::BrightYellow;
-pub const COLOR_NUM_VALUE: Color = Color::BrightGreen;
-pub const COLOR_TITLE: Color = Color::BrightYellow;
-pub const COLOR_GROUP: Color = Color::BrightBlue;
-pub const COLOR_IDENTIFIER: Color = Color::BrightMagenta;
-pub const COLOR_DONE_ITEM: Color = Color::BrightBlack;
-pub const COLOR_DONE_LABEL: Color = Color::BrightBlack;
-pub const COLOR_TODO_ITEM: Color = Color::BrightCyan;
-pub const COLOR_TODO_LABEL: Color = Color::BrightMagenta;
+const COLUMN_GAP: &str = "  ";
+/// The narrowest a column gets when a table is cut to fit the terminal
+const MIN_COLUMN_WIDTH: usize = 4;
+const ELLIPSIS: char = '…';
+const BRANCH: &str = "├─ ";
+const LAST_BRANCH: &str = "└─ ";
+const TRUNK: &str = "│  ";
+const NO_TRUNK: &str = "   ";
+const DEFAULT_PAGER: &str = "less -R";
+/// The colours used to print each kind of information
+#[derive(Clone)]
+pub struct Palette
+{
+    pub prefix: Color,
+    pub success: Color,
+    pub error: Color,
+    pub info: Color,
+    pub number: Color,
+    pub title: Color,
+    pub group: Color,
+    pub identifier: Color,
+    pub done_item: Color,
+    pub done_label: Color,
+    pub todo_item: Color,
+    pub todo_label: Color,
+    pub date: Color,
+    pub recurrence: Color,
+    pub priority_a: Color,
+    pub priority_b: Color,
+    pub priority_c: Color,
+    pub context: Color,
+    pub estimate: Color,
+    pub blocked_item: Color,
+    pub tag: Color,
+    pub delegate: Color,
+    pub area: Color,
+    pub goal: Color,
+    pub link: Color,
+}
+
+impl Default for Palette
+{
+    fn default() -> Self { Palette::of(Theme::Dark) }
+}
+
+impl Palette
+{
+    /// The palette of `theme`, which for the monochrome theme is the dark
+    /// one, as colours are turned off altogether
+    pub fn of(theme: Theme) -> Self
+    {
+        match theme
+        {
+            Theme::Dark | Theme::Monochrome =>
+            {
+                Palette {
+                    prefix: Color::BrightGreen,
+                    success: Color::BrightGreen,
+                    error: Color::BrightRed,
+                    info: Color::BrightYellow,
+                    number: Color::BrightGreen,
+                    title: Color::BrightYellow,
+                    group: Color::BrightBlue,
+                    identifier: Color::BrightMagenta,
+                    done_item: Color::BrightBlack,
+                    done_label: Color::BrightBlack,
+                    todo_item: Color::BrightCyan,
+                    todo_label: Color::BrightMagenta,
+                    date: Color::BrightYellow,
+                    recurrence: Color::BrightBlue,
+                    priority_a: Color::BrightRed,
+                    priority_b: Color::BrightYellow,
+                    priority_c: Color::BrightBlue,
+                    context: Color::Cyan,
+                    estimate: Color::White,
+                    blocked_item: Color::BrightBlack,
+                    tag: Color::Yellow,
+                    delegate: Color::BrightCyan,
+                    area: Color::BrightBlue,
+                    goal: Color::BrightGreen,
+                    link: Color::BrightBlue,
+                }
+            }
+            Theme::Light =>
+            {
+                Palette {
+                    prefix: Color::Blue,
+                    success: Color::Green,
+                    error: Color::Red,
+                    info: Color::Magenta,
+                    number: Color::Blue,
+                    title: Color::Magenta,
+                    group: Color::Blue,
+                    identifier: Color::Magenta,
+                    done_item: Color::BrightBlack,
+                    done_label: Color::BrightBlack,
+                    todo_item: Color::Black,
+                    todo_label: Color::Blue,
+                    date: Color::Blue,
+                    recurrence: Color::Cyan,
+                    priority_a: Color::Red,
+                    priority_b: Color::Magenta,
+                    priority_c: Color::Blue,
+                    context: Color::Cyan,
+                    estimate: Color::Black,
+                    blocked_item: Color::BrightBlack,
+                    tag: Color::Magenta,
+                    delegate: Color::Blue,
+                    area: Color::Blue,
+                    goal: Color::Green,
+                    link: Color::Blue,
+                }
+            }
+            Theme::HighContrast =>
+            {
+                Palette {
+                    prefix: Color::BrightWhite,
+                    success: Color::BrightGreen,
+                    error: Color::BrightRed,
+                    info: Color::BrightYellow,
+                    number: Color::BrightWhite,
+                    title: Color::BrightWhite,
+                    group: Color::BrightCyan,
+                    identifier: Color::BrightYellow,
+                    done_item: Color::White,
+                    done_label: Color::BrightGreen,
+                    todo_item: Color::BrightWhite,
+                    todo_label: Color::BrightYellow,
+                    date: Color::BrightCyan,
+                    recurrence: Color::BrightCyan,
+                    priority_a: Color::BrightRed,
+                    priority_b: Color::BrightYellow,
+                    priority_c: Color::BrightCyan,
+                    context: Color::BrightCyan,
+                    estimate: Color::BrightWhite,
+                    blocked_item: Color::White,
+                    tag: Color::BrightYellow,
+                    delegate: Color::BrightCyan,
+                    area: Color::BrightCyan,
+                    goal: Color::BrightGreen,
+                    link: Color::BrightCyan,
+                }
+            }
+        }
+    }
+
+    /// Each colour of the palette, along with the name it is configured by
+    fn slots_mut(&mut self) -> [(&'static str, &mut Color); 25]
+    {
+        [
+            ("prefix", &mut self.prefix),
+            ("success", &mut self.success),
+            ("error", &mut self.error),
+            ("info", &mut self.info),
+            ("number", &mut self.number),
+            ("title", &mut self.title),
+            ("group", &mut self.group),
+            ("identifier", &mut self.identifier),
+            ("done-item", &mut self.done_item),
+            ("done-label", &mut self.done_label),
+            ("todo-item", &mut self.todo_item),
+            ("todo-label", &mut self.todo_label),
+            ("date", &mut self.date),
+            ("recurrence", &mut self.recurrence),
+            ("priority-a", &mut self.priority_a),
+            ("priority-b", &mut self.priority_b),
+            ("priority-c", &mut self.priority_c),
+            ("context", &mut self.context),
+            ("estimate", &mut self.estimate),
+            ("blocked-item", &mut self.blocked_item),
+            ("tag", &mut self.tag),
+            ("delegate", &mut self.delegate),
+            ("area", &mut self.area),
+            ("goal", &mut self.goal),
+            ("link", &mut self.link),
+        ]
+    }
+
+    /// The names of the colours of the palette
+    pub fn keys() -> Vec<&'static str>
+    {
+        Palette::default()
+            .slots_mut()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Color>
+    {
+        self.clone()
+            .slots_mut()
+            .into_iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, color)| *color)
+    }
+
+    pub fn set(&mut self, key: &str, color: Color) -> EResult<()>
+    {
+        match self.slots_mut().into_iter().find(|(name, _)| *name == key)
+        {
+            Some((_, slot)) =>
+            {
+                *slot = color;
+                Ok(())
+            }
+            None =>
+            {
+                Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("There is no colour named \"{}\".", key),
+                )))
+            }
+        }
+    }
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// The palette in use, which is the default one unless `use_palette` was
+/// called at startup
+pub fn palette() -> &'static Palette { PALETTE.get_or_init(Palette::default) }
+
+/// Print everything with `palette` from now on
+pub fn use_palette(palette: Palette) { let _ = PALETTE.set(palette); }
+
+static PAGER: OnceLock<bool> = OnceLock::new();
+
+/// Show output taller than the terminal through the pager from now on, if
+/// `enabled`
+pub fn use_pager(enabled: bool) { let _ = PAGER.set(enabled); }
+
+/// Show `text` through `$PAGER` if paging is on and the text doesn't fit in
+/// the terminal, returning whether it was shown
+fn page(text: &str) -> bool
+{
+    if !PAGER.get().copied().unwrap_or(false) || !io::stdout().is_terminal()
+    {
+        return false;
+    }
+
+    let Ok((width, height)) = terminal::size()
+    else
+    {
+        return false;
+    };
+
+    let rows: usize = text
+        .lines()
+        .map(|line| display_width(line).max(1).div_ceil(width.max(1) as usize))
+        .sum();
+
+    // Keep a row for the shell prompt that follows the output
+    if rows < height as usize
+    {
+        return false;
+    }
+
+    // An empty `$PAGER` turns paging off
+    let command = match env::var("PAGER")
+    {
+        Ok(command) if command.trim().is_empty() => return false,
+        Ok(command) => command,
+        Err(_) => DEFAULT_PAGER.to_owned(),
+    };
+
+    let Ok(words) = shell_words::split(&command)
+    else
+    {
+        return false;
+    };
+
+    let Some((program, arguments)) = words.split_first()
+    else
+    {
+        return false;
+    };
+
+    let Ok(mut pager) = Command::new(program)
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .spawn()
+    else
+    {
+        return false;
+    };
+
+    if let Some(mut input) = pager.stdin.take()
+    {
+        // The pager may be quit before reading everything
+        let _ = input.write_all(text.as_bytes());
+    }
+
+    let _ = pager.wait();
+
+    true
+}
+
+/// Decide whether to print colours from now on: with `ColorChoice::Auto`,
+/// a non-empty NO_COLOR turns them off, a CLICOLOR_FORCE other than 0 turns
+/// them on, and otherwise they are printed only to terminals
+pub fn use_colors(choice: ColorChoice)
+{
+    let enabled = match choice
+    {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto =>
+        {
+            let set = |name: &str| {
+                env::var(name).ok().filter(|value| !value.is_empty())
+            };
+
+            if set("NO_COLOR").is_some()
+            {
+                false
+            }
+            else if set("CLICOLOR_FORCE").is_some_and(|value| value != "0")
+            {
+                true
+            }
+            else
+            {
+                io::stdout().is_terminal()
+            }
+        }
+    };
+
+    colored::control::set_override(enabled);
+}
+
+/// Whether colours are printed
+pub fn colors_enabled() -> bool
+{
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+const COLOR_NAMES: [(Color, &str); 16] = [
+    (Color::Black, "black"),
+    (Color::Red, "red"),
+    (Color::Green, "green"),
+    (Color::Yellow, "yellow"),
+    (Color::Blue, "blue"),
+    (Color::Magenta, "magenta"),
+    (Color::Cyan, "cyan"),
+    (Color::White, "white"),
+    (Color::BrightBlack, "bright-black"),
+    (Color::BrightRed, "bright-red"),
+    (Color::BrightGreen, "bright-green"),
+    (Color::BrightYellow, "bright-yellow"),
+    (Color::BrightBlue, "bright-blue"),
+    (Color::BrightMagenta, "bright-magenta"),
+    (Color::BrightCyan, "bright-cyan"),
+    (Color::BrightWhite, "bright-white"),
+];
+
+/// Parse a colour name such as "bright-red", or a hex code such as "#ff8800"
+pub fn parse_color(source: &str) -> EResult<Color>
+{
+    let source = source.trim().to_lowercase().replace([' ', '_'], "-");
+
+    if let Some((color, _)) =
+        COLOR_NAMES.iter().find(|(_, name)| *name == source)
+    {
+        return Ok(*color);
+    }
+
+    if let Some(hex) = source.strip_prefix('#')
+    {
+        if let (6, Ok(value)) = (hex.len(), u32::from_str_radix(hex, 16))
+        {
+            return Ok(Color::TrueColor {
+                r: (value >> 16) as u8,
+                g: (value >> 8) as u8,
+                b: value as u8,
+            });
+        }
+    }
+
+    Err(Box::new(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("No colour matches \"{}\".", source),
+    )))
+}
+
+pub fn color_name(color: Color) -> String
+{
+    match COLOR_NAMES.iter().find(|(known, _)| *known == color)
+    {
+        Some((_, name)) => name.to_string(),
+        None =>
+        {
+            match color
+            {
+                Color::TrueColor { r, g, b } =>
+                {
+                    format!("#{:02x}{:02x}{:02x}", r, g, b)
+                }
+                _ => String::new(),
+            }
+        }
+    }
+}
 
 pub trait OutputFormattable
 {
     fn tos_format(&self) -> String;
 }
 
+/// The width of the terminal output is printed to, if it is printed to one
+pub fn terminal_width() -> Option<usize>
+{
+    if !io::stdout().is_terminal()
+    {
+        return None;
+    }
+
+    terminal::size().ok().map(|(width, _)| width as usize)
+}
+
+/// Remove the colour codes from `text`
+fn strip_escapes(text: &str) -> String
+{
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next()
+    {
+        if c == '\x1b'
+        {
+            chars.by_ref().find(char::is_ascii_alphabetic);
+        }
+        else
+        {
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+/// The amount of columns `text` takes on screen
+pub fn display_width(text: &str) -> usize { strip_escapes(text).width() }
+
+/// Cut `text` down to `width` columns, ending it with an ellipsis if anything
+/// had to be left out
+pub fn truncate(text: &str, width: usize) -> String
+{
+    if display_width(text) <= width
+    {
+        return text.to_owned();
+    }
+
+    let mut truncated = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next()
+    {
+        truncated.push(c);
+
+        if c == '\x1b'
+        {
+            // Keep the colour codes, so that the ellipsis has the colour of
+            // the text it replaces
+            for code in chars.by_ref()
+            {
+                truncated.push(code);
+
+                if code.is_ascii_alphabetic()
+                {
+                    break;
+                }
+            }
+
+            continue;
+        }
+
+        used += c.width().unwrap_or(0);
+
+        if used >= width
+        {
+            truncated.pop();
+            break;
+        }
+    }
+
+    if width > 0
+    {
+        truncated.push(ELLIPSIS);
+    }
+
+    if text.contains('\x1b')
+    {
+        truncated.push_str("\x1b[0m");
+    }
+
+    truncated
+}
+
+/// Lay out `words` in lines no wider than `width` columns, leaving a word on
+/// a line of its own if it is wider than that
+pub fn wrap(words: &[String], width: Option<usize>) -> Vec<String>
+{
+    let mut lines: Vec<String> = vec![];
+    let mut line = String::new();
+    let mut line_width = 0;
+
+    for word in words
+    {
+        let word_width = display_width(word);
+
+        if line_width > 0
+            && width.is_some_and(|width| line_width + 1 + word_width > width)
+        {
+            lines.push(line);
+            line = String::new();
+            line_width = 0;
+        }
+
+        if line_width > 0
+        {
+            line.push(' ');
+            line_width += 1;
+        }
+
+        line.push_str(word);
+        line_width += word_width;
+    }
+
+    if line_width > 0
+    {
+        lines.push(line);
+    }
+
+    lines
+}
+
 pub fn get_padding_length(level: usize) -> usize
 {
     BASE_PADDING_LEVEL + level * 2
@@ -43,20 +588,56 @@ pub fn get_padding(level: usize) -> String
 
 pub fn format_number(number: impl ToString) -> String
 {
-    format!("{}", number.to_string().color(COLOR_NUM_VALUE))
+    format!("{}", number.to_string().color(palette().number))
 }
 
 pub fn format_index(index: usize) -> String
 {
     format!(
         "{}",
-        &indexer::index_to_identifier(index).color(COLOR_NUM_VALUE)
+        &indexer::index_to_identifier(index).color(palette().number)
     )
 }
 
 pub fn format_list_name(name: &str) -> String
 {
-    format!("{}", name.to_titlecase().color(COLOR_IDENTIFIER))
+    format!("{}", name.to_titlecase().color(palette().identifier))
+}
+
+pub fn format_list_kind(kind: &ListKind) -> String
+{
+    kind.label().color(palette().group).to_string()
+}
+
+pub fn format_delegate(person: &str) -> String
+{
+    person.color(palette().delegate).bold().to_string()
+}
+
+/// Format a follow-up date, highlighting it if it has already passed
+pub fn format_follow_up(date: &NaiveDate, today: NaiveDate) -> String
+{
+    let formatted = dates::format_date(date);
+
+    if *date < today
+    {
+        format!("{} {}", formatted, "OVERDUE")
+            .color(palette().error)
+            .bold()
+            .to_string()
+    }
+    else
+    {
+        formatted.color(palette().date).to_string()
+    }
+}
+
+pub fn format_tickler(date: &NaiveDate) -> String
+{
+    format!(
+        "resurfaces {}",
+        dates::format_date(date).color(palette().date)
+    )
 }
 
 pub fn format_progress(done: usize, total: usize) -> String
@@ -73,8 +654,18 @@ pub fn format_status(status: &Status) -> String
 {
     match status
     {
-        Status::TODO => "TODO".color(COLOR_TODO_LABEL).bold().to_string(),
-        Status::DONE => "DONE".color(COLOR_DONE_LABEL).bold().to_string(),
+        Status::TODO => "TODO".color(palette().todo_label).bold().to_string(),
+        Status::DONE => "DONE".color(palette().done_label).bold().to_string(),
+    }
+}
+
+pub fn format_priority(priority: &Priority) -> String
+{
+    match priority
+    {
+        Priority::A => "(A)".color(palette().priority_a).bold().to_string(),
+        Priority::B => "(B)".color(palette().priority_b).bold().to_string(),
+        Priority::C => "(C)".color(palette().priority_c).bold().to_string(),
     }
 }
 
@@ -85,20 +676,85 @@ pub fn format_project_name(name: &str, status: &Status) -> String
         name.to_titlecase()
             .color(match status
             {
-                Status::TODO => COLOR_TODO_ITEM,
-                Status::DONE => COLOR_DONE_ITEM,
+                Status::TODO => palette().todo_item,
+                Status::DONE => palette().done_item,
             })
             .bold()
     )
 }
 
-pub fn format_project(name: &str, status: &Status) -> String
+pub fn format_project(project: &Project) -> String
 {
-    format!(
+    let status = project.status();
+
+    let mut formatted = format_status(&status);
+
+    if let Some(priority) = &project.priority
+    {
+        formatted.push_str(&format!(" {}", format_priority(priority)));
+    }
+
+    formatted.push_str(&format!(
+        " {}",
+        format_project_name(&project.name, &status)
+    ));
+
+    if project.sequential
+    {
+        formatted
+            .push_str(&format!(" {}", "(sequential)".color(palette().info)));
+    }
+
+    for tag in &project.tags
+    {
+        formatted.push_str(&format!(" {}", format_tag(tag)));
+    }
+
+    if let Some(tickler) = &project.tickler
+    {
+        formatted.push_str(&format!(" ({})", format_tickler(tickler)));
+    }
+
+    formatted
+}
+
+pub fn format_area_name(name: &str) -> String
+{
+    name.to_titlecase().color(palette().area).bold().to_string()
+}
+
+pub fn format_goal_name(name: &str) -> String
+{
+    name.to_titlecase().color(palette().goal).to_string()
+}
+
+pub fn format_goal(goal: &Goal) -> String
+{
+    let mut formatted = format!(
         "{} {}",
-        format_status(status),
-        format_project_name(name, status),
-    )
+        "Goal:".color(palette().goal),
+        format_goal_name(&goal.name)
+    );
+
+    if let Some(due) = &goal.due
+    {
+        formatted.push_str(&format!(" ({})", format_due_date(due)));
+    }
+
+    formatted
+}
+
+pub fn format_no_active_projects() -> String
+{
+    "NO ACTIVE PROJECTS"
+        .color(palette().error)
+        .bold()
+        .to_string()
+}
+
+pub fn format_stalled() -> String
+{
+    "STALLED".color(palette().error).bold().to_string()
 }
 
 pub fn format_task_name(task: &Task) -> String
@@ -107,24 +763,155 @@ pub fn format_task_name(task: &Task) -> String
         .to_titlecase()
         .color(match task.status
         {
-            Status::TODO => COLOR_TODO_ITEM,
-            Status::DONE => COLOR_DONE_ITEM,
+            Status::TODO => palette().todo_item,
+            Status::DONE => palette().done_item,
         })
         .to_string()
 }
 
-pub fn format_task(task: &Task) -> String
+pub fn format_checklist_item(item: &ChecklistItem) -> String
 {
+    let (mark, color) = if item.checked
+    {
+        ("[x]", palette().done_item)
+    }
+    else
+    {
+        ("[ ]", palette().todo_item)
+    };
+
     format!(
         "{} {}",
-        format_status(&task.status),
-        format_task_name(&task),
+        mark.color(color).bold(),
+        item.name.to_titlecase().color(color)
     )
 }
 
+pub fn format_due_date(date: &NaiveDate) -> String
+{
+    format!("due {}", dates::format_date(date).color(palette().date))
+}
+
+pub fn format_recurrence(recurrence: &Recurrence) -> String
+{
+    format!("↻ {}", recurrence)
+        .color(palette().recurrence)
+        .to_string()
+}
+
+pub fn format_context(context: &str) -> String
+{
+    context.color(palette().context).to_string()
+}
+
+pub fn format_tag(tag: &str) -> String { tag.color(palette().tag).to_string() }
+
+pub fn format_energy(energy: &Energy) -> String
+{
+    match energy
+    {
+        Energy::Low => "low energy",
+        Energy::Medium => "medium energy",
+        Energy::High => "high energy",
+    }
+    .color(palette().estimate)
+    .to_string()
+}
+
+pub fn format_estimate(minutes: u32) -> String
+{
+    dates::format_duration(minutes)
+        .color(palette().estimate)
+        .to_string()
+}
+
+/// Format a task, dimming it if it is waiting on any of the `blockers`
+pub fn format_task(task: &Task, blockers: &[&Task]) -> String
+{
+    let mut formatted = format_status(&task.status);
+
+    if let Some(priority) = &task.priority
+    {
+        formatted.push_str(&format!(" {}", format_priority(priority)));
+    }
+
+    if blockers.is_empty()
+    {
+        formatted.push_str(&format!(" {}", format_task_name(task)));
+    }
+    else
+    {
+        formatted.push_str(&format!(
+            " {}",
+            task.name.to_titlecase().color(palette().blocked_item)
+        ));
+    }
+
+    for context in &task.contexts
+    {
+        formatted.push_str(&format!(" {}", format_context(context)));
+    }
+
+    for tag in &task.tags
+    {
+        formatted.push_str(&format!(" {}", format_tag(tag)));
+    }
+
+    let requirements: Vec<String> = task
+        .estimate
+        .map(format_estimate)
+        .into_iter()
+        .chain(task.energy.as_ref().map(format_energy))
+        .collect();
+
+    if !requirements.is_empty()
+    {
+        formatted.push_str(&format!(" [{}]", requirements.join(", ")));
+    }
+
+    if let Some(due) = &task.due
+    {
+        formatted.push_str(&format!(" ({})", format_due_date(due)));
+    }
+
+    if let Some(tickler) = &task.tickler
+    {
+        formatted.push_str(&format!(" ({})", format_tickler(tickler)));
+    }
+
+    if let Some(delegate) = &task.delegate
+    {
+        formatted.push_str(&format!(
+            " (delegated to {})",
+            format_delegate(delegate)
+        ));
+    }
+
+    if let Some(recurrence) = &task.recurrence
+    {
+        formatted.push_str(&format!(" {}", format_recurrence(recurrence)));
+    }
+
+    if !blockers.is_empty()
+    {
+        let names: Vec<String> = blockers
+            .iter()
+            .map(|blocker| blocker.name.to_titlecase())
+            .collect();
+
+        formatted.push_str(
+            &format!(" (waiting on {})", names.join(", "))
+                .color(palette().blocked_item)
+                .to_string(),
+        );
+    }
+
+    formatted
+}
+
 pub fn format_section_name(name: &str) -> String
 {
-    format!("{}", name.to_titlecase().color(COLOR_GROUP).bold())
+    format!("{}", name.to_titlecase().color(palette().group).bold())
 }
 
 pub struct OutputBlock
@@ -163,9 +950,253 @@ impl OutputBlock
         self
     }
 
+    /// Insert the rows of `table`, cut short to fit in the terminal
+    pub fn insert_table(
+        &mut self,
+        table: &Table,
+        padding_level: usize,
+    ) -> &mut Self
+    {
+        let widths = table.widths(terminal_width().map(|width| {
+            width.saturating_sub(get_padding_length(padding_level))
+        }));
+
+        for row in &table.rows
+        {
+            let mut cells: Vec<String> = vec![];
+
+            for (column, cell) in row.iter().enumerate()
+            {
+                let width = widths[column];
+                let text = truncate(cell, width);
+                let filler = " ".repeat(width - display_width(&text));
+
+                cells.push(match table.columns[column]
+                {
+                    Align::Right => format!("{}{}", filler, text),
+                    Align::Left if column == row.len() - 1 => text,
+                    Align::Left => format!("{}{}", text, filler),
+                });
+            }
+
+            self.insert_line(cells.join(COLUMN_GAP).trim_end(), padding_level);
+        }
+
+        self
+    }
+
+    /// Insert the label of `root`, followed by the labels of the items it
+    /// holds, joined to it by box-drawing lines
+    pub fn insert_tree(
+        &mut self,
+        root: &TreeNode,
+        padding_level: usize,
+    ) -> &mut Self
+    {
+        let width = terminal_width().map(|width| {
+            width.saturating_sub(get_padding_length(padding_level))
+        });
+
+        let fit = |line: String| {
+            match width
+            {
+                Some(width) => truncate(&line, width),
+                None => line,
+            }
+        };
+
+        self.insert_line(&fit(root.label.clone()), padding_level);
+
+        let mut branches: Vec<String> = vec![];
+        insert_description(&mut branches, root, "", width);
+        insert_branches(&mut branches, &root.children, "", width);
+
+        for branch in branches
+        {
+            // Blank lines between the paragraphs of a description
+            if branch.is_empty()
+            {
+                self.insert_text("\n");
+                continue;
+            }
+
+            self.insert_line(&fit(branch), padding_level);
+        }
+
+        self
+    }
+
+    pub fn text(&self) -> &str { &self.text }
+
     pub fn send(&self) -> ()
     {
-        println!("{}{}{}", BLOCK_PREFIX, &self.text.trim_end(), BLOCK_POSTFIX,);
+        let text = format!(
+            "{}{}{}",
+            BLOCK_PREFIX,
+            &self.text.trim_end(),
+            BLOCK_POSTFIX
+        );
+
+        if !page(&format!("{}\n", text))
+        {
+            println!("{}", text);
+        }
+    }
+}
+
+/// How the cells of a column are lined up
+#[derive(Clone, Copy)]
+pub enum Align
+{
+    Left,
+    Right,
+}
+
+/// Rows of cells printed in aligned columns
+pub struct Table
+{
+    columns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table
+{
+    pub fn new(columns: &[Align]) -> Self
+    {
+        Self {
+            columns: columns.to_vec(),
+            rows: vec![],
+        }
+    }
+
+    /// Add a row with a cell for each column
+    pub fn add_row(&mut self, cells: Vec<String>) -> &mut Self
+    {
+        debug_assert_eq!(cells.len(), self.columns.len());
+
+        self.rows.push(cells);
+
+        self
+    }
+
+    /// The width of each column, narrowing the widest ones until the rows
+    /// fit in `available` columns
+    fn widths(&self, available: Option<usize>) -> Vec<usize>
+    {
+        let mut widths: Vec<usize> = (0..self.columns.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| display_width(&row[column]))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let Some(available) = available
+        else
+        {
+            return widths;
+        };
+
+        let gaps = COLUMN_GAP.len() * widths.len().saturating_sub(1);
+
+        while widths.iter().sum::<usize>() + gaps > available
+        {
+            match widths.iter_mut().max()
+            {
+                Some(width) if *width > MIN_COLUMN_WIDTH => *width -= 1,
+                _ => break,
+            }
+        }
+
+        widths
+    }
+}
+
+/// An item, along with its description and the items it holds
+pub struct TreeNode
+{
+    pub label: String,
+    /// Markdown shown under the label, wrapped to the terminal width
+    pub description: Option<String>,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode
+{
+    pub fn new(label: String) -> Self
+    {
+        Self {
+            label,
+            description: None,
+            children: vec![],
+        }
+    }
+}
+
+/// Push the lines of the description of `node`, whose children hang from
+/// `prefix`, keeping the branch to its children going alongside them
+fn insert_description(
+    lines: &mut Vec<String>,
+    node: &TreeNode,
+    prefix: &str,
+    width: Option<usize>,
+)
+{
+    let Some(description) = &node.description
+    else
+    {
+        return;
+    };
+
+    let prefix = format!(
+        "{}{}",
+        prefix,
+        if node.children.is_empty()
+        {
+            NO_TRUNK
+        }
+        else
+        {
+            TRUNK
+        }
+    );
+
+    let width =
+        width.map(|width| width.saturating_sub(display_width(&prefix)));
+
+    for line in markdown::render(description, width)
+    {
+        lines.push(format!("{}{}", prefix, line).trim_end().to_owned());
+    }
+}
+
+/// Push a line for each of the `nodes` and their children, drawing the
+/// branches that lead to them after `prefix`
+fn insert_branches(
+    lines: &mut Vec<String>,
+    nodes: &[TreeNode],
+    prefix: &str,
+    width: Option<usize>,
+)
+{
+    for (position, node) in nodes.iter().enumerate()
+    {
+        let last = position == nodes.len() - 1;
+
+        lines.push(format!(
+            "{}{}{}",
+            prefix,
+            if last { LAST_BRANCH } else { BRANCH },
+            node.label
+        ));
+
+        let prefix =
+            format!("{}{}", prefix, if last { NO_TRUNK } else { TRUNK });
+
+        insert_description(lines, node, &prefix, width);
+        insert_branches(lines, &node.children, &prefix, width);
     }
 }
 
@@ -173,7 +1204,10 @@ impl OutputBlock
 pub fn send_info(message: &str) -> ()
 {
     OutputBlock::new()
-        .insert_line(&format!("{} {}", PREFIX.color(COLOR_INFO), message), 0)
+        .insert_line(
+            &format!("{} {}", PREFIX.color(palette().info), message),
+            0,
+        )
         .send()
 }
 
@@ -181,7 +1215,7 @@ pub fn send_error(message: &str) -> ()
 {
     OutputBlock::new()
         .insert_line(
-            &format!("{} Error: {}", PREFIX.color(COLOR_ERROR), message),
+            &format!("{} Error: {}", PREFIX.color(palette().error), message),
             0,
         )
         .send()
@@ -191,7 +1225,7 @@ pub fn send_success(message: &str) -> ()
 {
     OutputBlock::new()
         .insert_line(
-            &format!("{} {}", PREFIX.color(COLOR_SUCCESS), message),
+            &format!("{} {}", PREFIX.color(palette().success), message),
             0,
         )
         .send()
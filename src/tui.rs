@@ -0,0 +1,1290 @@
+use std::io;
+use std::io::IsTerminal;
+
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::Event;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::crossterm::event::KeyEvent;
+use ratatui::crossterm::event::KeyEventKind;
+use ratatui::crossterm::event::KeyModifiers;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::List as ListWidget;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+
+use crate::capture;
+use crate::config;
+use crate::dates;
+use crate::gtd::File;
+use crate::gtd::List;
+use crate::gtd::ListContainer;
+use crate::gtd::Priority;
+use crate::gtd::Project;
+use crate::gtd::ProjectContainer;
+use crate::gtd::Status;
+use crate::gtd::Task;
+use crate::gtd::TaskContainer;
+use crate::gtd::TaskIndex;
+use crate::itempath::ContainerPath;
+use crate::itempath::TaskPath;
+use crate::text::Formattable;
+use crate::tos;
+use crate::EResult;
+
+const SIDEBAR_WIDTH: u16 = 28;
+
+/// The pane that receives the navigation keys
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Focus
+{
+    Lists,
+    Items,
+}
+
+/// A project or a task shown in the items pane
+#[derive(Clone)]
+enum Item
+{
+    Project(ContainerPath),
+    Task(TaskPath),
+}
+
+/// A row of the items pane: an item and how deeply it is nested
+struct Row
+{
+    item: Item,
+    depth: usize,
+}
+
+/// What the text typed into the input line is for
+enum Input
+{
+    NewTask(ContainerPath),
+    NewProject(ContainerPath),
+    NewList,
+    Rename(Item),
+    RenameList(String),
+    Due(TaskPath),
+}
+
+enum Mode
+{
+    Normal,
+    /// Typing into the input line
+    Typing
+    {
+        input: Input,
+        buffer: String,
+    },
+    /// Choosing the list to move an item to, coming from the list at `from`
+    Moving
+    {
+        item: Item,
+        from: usize,
+    },
+    /// Waiting for the user to confirm the removal of an item
+    Removing(Item),
+}
+
+/// A message shown in the status line after an action
+struct Message
+{
+    text: String,
+    error: bool,
+}
+
+struct App<'a>
+{
+    file: &'a mut File,
+    file_path: &'a str,
+    focus: Focus,
+    mode: Mode,
+    lists: ListState,
+    items: ListState,
+    message: Option<Message>,
+    quit: bool,
+}
+
+/// Convert a colour of the `tos` palette to its terminal UI counterpart
+fn color(color: colored::Color) -> Color
+{
+    if !tos::colors_enabled()
+    {
+        return Color::Reset;
+    }
+
+    match color
+    {
+        colored::Color::Black => Color::Black,
+        colored::Color::Red => Color::Red,
+        colored::Color::Green => Color::Green,
+        colored::Color::Yellow => Color::Yellow,
+        colored::Color::Blue => Color::Blue,
+        colored::Color::Magenta => Color::Magenta,
+        colored::Color::Cyan => Color::Cyan,
+        colored::Color::White => Color::Gray,
+        colored::Color::BrightBlack => Color::DarkGray,
+        colored::Color::BrightRed => Color::LightRed,
+        colored::Color::BrightGreen => Color::LightGreen,
+        colored::Color::BrightYellow => Color::LightYellow,
+        colored::Color::BrightBlue => Color::LightBlue,
+        colored::Color::BrightMagenta => Color::LightMagenta,
+        colored::Color::BrightCyan => Color::LightCyan,
+        colored::Color::BrightWhite => Color::White,
+        colored::Color::TrueColor { r, g, b } => Color::Rgb(r, g, b),
+    }
+}
+
+fn styled(
+    text: impl Into<String>,
+    palette_color: colored::Color,
+) -> Span<'static>
+{
+    Span::styled(text.into(), Style::new().fg(color(palette_color)))
+}
+
+fn status_span(status: &Status) -> Span<'static>
+{
+    match status
+    {
+        Status::TODO => styled("[ ]", tos::palette().todo_label).bold(),
+        Status::DONE => styled("[x]", tos::palette().done_label).bold(),
+    }
+}
+
+fn priority_span(priority: &Priority) -> Span<'static>
+{
+    match priority
+    {
+        Priority::A => styled("(A)", tos::palette().priority_a).bold(),
+        Priority::B => styled("(B)", tos::palette().priority_b).bold(),
+        Priority::C => styled("(C)", tos::palette().priority_c).bold(),
+    }
+}
+
+fn item_color(status: &Status) -> colored::Color
+{
+    match status
+    {
+        Status::TODO => tos::palette().todo_item,
+        Status::DONE => tos::palette().done_item,
+    }
+}
+
+fn error(kind: io::ErrorKind, message: &str) -> Box<io::Error>
+{
+    Box::new(io::Error::new(kind, message))
+}
+
+/// Flatten the projects and tasks of a container into rows, projects first
+fn push_rows(
+    rows: &mut Vec<Row>,
+    projects: &[Project],
+    tasks: &[Task],
+    path: &ContainerPath,
+    depth: usize,
+)
+{
+    for (index, project) in projects.iter().enumerate()
+    {
+        let project_path = path.child(index);
+
+        rows.push(Row {
+            item: Item::Project(project_path.clone()),
+            depth,
+        });
+
+        push_rows(
+            rows,
+            project.projects(),
+            project.tasks(),
+            &project_path,
+            depth + 1,
+        );
+    }
+
+    for index in 0..tasks.len()
+    {
+        rows.push(Row {
+            item: Item::Task(path.task(index)),
+            depth,
+        });
+    }
+}
+
+impl<'a> App<'a>
+{
+    fn new(file: &'a mut File, file_path: &'a str) -> Self
+    {
+        let mut lists = ListState::default();
+
+        if !file.lists.is_empty()
+        {
+            lists.select(Some(0));
+        }
+
+        App {
+            file,
+            file_path,
+            focus: Focus::Lists,
+            mode: Mode::Normal,
+            lists,
+            items: ListState::default(),
+            message: None,
+            quit: false,
+        }
+    }
+
+    fn current_list(&self) -> Option<&List>
+    {
+        self.lists
+            .selected()
+            .and_then(|index| self.file.lists.get(index))
+    }
+
+    fn current_list_path(&self) -> Option<ContainerPath>
+    {
+        self.current_list().map(|list| {
+            ContainerPath {
+                list_name: list.name.clone(),
+                project_indexes: vec![],
+            }
+        })
+    }
+
+    fn rows(&self) -> Vec<Row>
+    {
+        let mut rows = vec![];
+
+        if let (Some(list), Some(path)) =
+            (self.current_list(), self.current_list_path())
+        {
+            push_rows(&mut rows, list.projects(), list.tasks(), &path, 0);
+        }
+
+        rows
+    }
+
+    fn selected_item(&self) -> Option<Item>
+    {
+        self.items
+            .selected()
+            .and_then(|index| self.rows().into_iter().nth(index))
+            .map(|row| row.item)
+    }
+
+    /// The container new items are added to: the selected project, the
+    /// project of the selected task, or else the selected list itself
+    fn target_container(&self) -> Option<ContainerPath>
+    {
+        if self.focus == Focus::Lists
+        {
+            return self.current_list_path();
+        }
+
+        match self.selected_item()
+        {
+            Some(Item::Project(path)) => Some(path),
+            Some(Item::Task(path)) => Some(path.container()),
+            None => self.current_list_path(),
+        }
+    }
+
+    fn clamp_selection(&mut self)
+    {
+        let total_lists = self.file.lists.len();
+
+        match self.lists.selected()
+        {
+            _ if total_lists == 0 => self.lists.select(None),
+            Some(index) if index >= total_lists =>
+            {
+                self.lists.select(Some(total_lists - 1))
+            }
+            None => self.lists.select(Some(0)),
+            _ => (),
+        }
+
+        let total_rows = self.rows().len();
+
+        match self.items.selected()
+        {
+            _ if total_rows == 0 => self.items.select(None),
+            Some(index) if index >= total_rows =>
+            {
+                self.items.select(Some(total_rows - 1))
+            }
+            None => self.items.select(Some(0)),
+            _ => (),
+        }
+    }
+
+    fn set_message(&mut self, text: impl Into<String>, error: bool)
+    {
+        self.message = Some(Message {
+            text: text.into(),
+            error,
+        });
+    }
+
+    /// Report the outcome of a change, saving the workspace if it succeeded
+    fn apply(&mut self, result: EResult<String>)
+    {
+        match result.and_then(|text| {
+            self.file.write_to_file(self.file_path)?;
+            Ok(text)
+        })
+        {
+            Ok(text) => self.set_message(text, false),
+            Err(error) => self.set_message(error.to_string(), true),
+        }
+
+        self.clamp_selection();
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> EResult<()>
+    {
+        while !self.quit
+        {
+            self.clamp_selection();
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()?
+            {
+                if key.kind == KeyEventKind::Press
+                {
+                    self.handle_key(key);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent)
+    {
+        if key.code == KeyCode::Char('c')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            self.quit = true;
+            return;
+        }
+
+        match std::mem::replace(&mut self.mode, Mode::Normal)
+        {
+            Mode::Normal =>
+            {
+                self.message = None;
+                self.handle_normal_key(key);
+            }
+            Mode::Typing { input, mut buffer } =>
+            {
+                match key.code
+                {
+                    KeyCode::Enter =>
+                    {
+                        let result = self.submit(input, buffer.trim());
+                        self.apply(result);
+                    }
+                    KeyCode::Esc => (),
+                    KeyCode::Backspace =>
+                    {
+                        buffer.pop();
+                        self.mode = Mode::Typing { input, buffer };
+                    }
+                    KeyCode::Char(character) =>
+                    {
+                        buffer.push(character);
+                        self.mode = Mode::Typing { input, buffer };
+                    }
+                    _ => self.mode = Mode::Typing { input, buffer },
+                }
+            }
+            Mode::Moving { item, from } =>
+            {
+                match key.code
+                {
+                    KeyCode::Enter =>
+                    {
+                        let result = self.move_item(&item);
+                        self.lists.select(Some(from));
+                        self.focus = Focus::Items;
+                        self.apply(result);
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') =>
+                    {
+                        self.lists.select(Some(from));
+                        self.focus = Focus::Items;
+                    }
+                    _ =>
+                    {
+                        self.navigate(key.code);
+                        self.mode = Mode::Moving { item, from };
+                    }
+                }
+            }
+            Mode::Removing(item) =>
+            {
+                if let KeyCode::Char('y') = key.code
+                {
+                    let result = self.remove_item(&item);
+                    self.apply(result);
+                }
+                else
+                {
+                    self.set_message("Nothing was removed.", false);
+                }
+            }
+        }
+    }
+
+    /// Move the selection of the focused pane, returning whether the key was
+    /// a navigation key
+    fn navigate(&mut self, code: KeyCode) -> bool
+    {
+        let state = match self.focus
+        {
+            Focus::Lists => &mut self.lists,
+            Focus::Items => &mut self.items,
+        };
+
+        match code
+        {
+            KeyCode::Down | KeyCode::Char('j') => state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => state.select_previous(),
+            KeyCode::Home | KeyCode::Char('g') => state.select_first(),
+            KeyCode::End | KeyCode::Char('G') => state.select_last(),
+            _ => return false,
+        }
+
+        if self.focus == Focus::Lists
+        {
+            self.items.select(Some(0));
+        }
+
+        self.clamp_selection();
+
+        true
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent)
+    {
+        if self.navigate(key.code)
+        {
+            return;
+        }
+
+        match (self.focus, key.code)
+        {
+            (_, KeyCode::Char('q') | KeyCode::Esc) => self.quit = true,
+            (_, KeyCode::Tab) =>
+            {
+                self.focus = match self.focus
+                {
+                    Focus::Lists => Focus::Items,
+                    Focus::Items => Focus::Lists,
+                }
+            }
+            (_, KeyCode::Left | KeyCode::Char('h')) =>
+            {
+                self.focus = Focus::Lists
+            }
+            (
+                Focus::Lists,
+                KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter,
+            )
+            | (Focus::Items, KeyCode::Right | KeyCode::Char('l')) =>
+            {
+                self.focus = Focus::Items
+            }
+            (_, KeyCode::Char('n')) => self.start_typing(Input::NewList, ""),
+            (_, KeyCode::Char('a')) =>
+            {
+                if let Some(path) = self.target_container()
+                {
+                    self.start_typing(Input::NewTask(path), "");
+                }
+            }
+            (_, KeyCode::Char('A')) =>
+            {
+                if let Some(path) = self.target_container()
+                {
+                    self.start_typing(Input::NewProject(path), "");
+                }
+            }
+            (Focus::Lists, KeyCode::Char('e')) =>
+            {
+                if let Some(name) = self.current_list().map(|l| l.name.clone())
+                {
+                    self.start_typing(Input::RenameList(name.clone()), &name);
+                }
+            }
+            (Focus::Items, _) => self.handle_item_key(key.code),
+            _ => (),
+        }
+    }
+
+    fn handle_item_key(&mut self, code: KeyCode)
+    {
+        let item = match self.selected_item()
+        {
+            Some(item) => item,
+            None => return,
+        };
+
+        match code
+        {
+            KeyCode::Char(' ') | KeyCode::Char('x') | KeyCode::Enter =>
+            {
+                let result = match &item
+                {
+                    Item::Task(path) => self.toggle_task(path),
+                    Item::Project(_) =>
+                    {
+                        Err(error(
+                            io::ErrorKind::InvalidInput,
+                            "A project is done once all of its tasks are.",
+                        )
+                        .into())
+                    }
+                };
+
+                self.apply(result);
+            }
+            KeyCode::Char('e') =>
+            {
+                let name = self.item_name(&item);
+
+                self.start_typing(Input::Rename(item), &name);
+            }
+            KeyCode::Char('u') =>
+            {
+                if let Item::Task(path) = item
+                {
+                    let due = self
+                        .file
+                        .get_task(&path)
+                        .ok()
+                        .and_then(|task| task.due)
+                        .map(|due| due.format(dates::DATE_FORMAT).to_string())
+                        .unwrap_or_default();
+
+                    self.start_typing(Input::Due(path), &due);
+                }
+            }
+            KeyCode::Char('m') =>
+            {
+                if let Some(from) = self.lists.selected()
+                {
+                    self.mode = Mode::Moving { item, from };
+                    self.focus = Focus::Lists;
+                }
+            }
+            KeyCode::Char('d') if config::get().confirm() =>
+            {
+                self.mode = Mode::Removing(item)
+            }
+            KeyCode::Char('d') =>
+            {
+                let result = self.remove_item(&item);
+                self.apply(result);
+            }
+            _ => (),
+        }
+    }
+
+    fn start_typing(&mut self, input: Input, text: &str)
+    {
+        self.mode = Mode::Typing {
+            input,
+            buffer: text.to_string(),
+        };
+    }
+
+    fn toggle_task(&mut self, path: &TaskPath) -> EResult<String>
+    {
+        let task = self.file.get_task(path)?;
+
+        if !task.done()
+        {
+            let blockers = self.file.open_blockers(task);
+
+            if !blockers.is_empty()
+            {
+                let names: Vec<&str> = blockers
+                    .iter()
+                    .map(|blocker| blocker.name.as_str())
+                    .collect();
+
+                return Err(error(
+                    io::ErrorKind::InvalidInput,
+                    &format!("Task is waiting on {}.", names.join(", ")),
+                ));
+            }
+        }
+
+        let container = self.file.get_container_mut(&path.container())?;
+        let task = container.get_task_mut_forced(path.task_index)?;
+
+        task.status = match task.status
+        {
+            Status::TODO => Status::DONE,
+            Status::DONE => Status::TODO,
+        };
+
+        let mut text = format!(
+            "Task {} marked as {}.",
+            task.name.to_titlecase(),
+            match task.status
+            {
+                Status::TODO => "TODO",
+                Status::DONE => "DONE",
+            }
+        );
+
+        if task.done()
+        {
+            if let Some(next_task) = task.next_occurrence(dates::today())
+            {
+                if let Some(due) = &next_task.due
+                {
+                    text.push_str(&format!(
+                        " Next occurrence due {}.",
+                        dates::format_date(due)
+                    ));
+                }
+
+                container.push_task(next_task);
+            }
+        }
+
+        Ok(text)
+    }
+
+    fn submit(&mut self, input: Input, text: &str) -> EResult<String>
+    {
+        if text.is_empty() && !matches!(input, Input::Due(_))
+        {
+            return Err(error(
+                io::ErrorKind::InvalidInput,
+                "The name can not be empty.",
+            ));
+        }
+
+        match input
+        {
+            Input::NewTask(path) =>
+            {
+                let task = capture::parse_shorthand(text)?.task;
+                let container = self.file.get_container_mut(&path)?;
+
+                container.task_exists_forced(&task.name)?;
+
+                let message =
+                    format!("Task {} created.", task.name.to_titlecase());
+
+                container.push_task(task);
+
+                Ok(message)
+            }
+            Input::NewProject(path) =>
+            {
+                let name = text.to_lowercase();
+                let container = self.file.get_project_container_mut(&path)?;
+
+                container.project_exists_forced(&name)?;
+                container.push_project(Project::new(name));
+
+                Ok(format!("Project {} created.", text.to_titlecase()))
+            }
+            Input::NewList =>
+            {
+                self.file.list_exists_forced(text)?;
+                self.file.push_list(List::new(text.to_string()));
+                self.lists.select(Some(self.file.lists.len() - 1));
+                self.focus = Focus::Lists;
+
+                Ok(format!("List {} created.", text.to_titlecase()))
+            }
+            Input::RenameList(name) =>
+            {
+                if name != text
+                {
+                    self.file.list_exists_forced(text)?;
+                }
+
+                self.file.get_list_mut_forced(&name)?.name = text.to_string();
+
+                Ok(format!("List renamed to {}.", text.to_titlecase()))
+            }
+            Input::Rename(Item::Project(path)) =>
+            {
+                let name = text.to_lowercase();
+                let renamed = self.file.get_project(&path)?.name != name;
+
+                if let (true, Some((parent_path, _))) =
+                    (renamed, path.split_last())
+                {
+                    self.file
+                        .get_project_container(&parent_path)?
+                        .project_exists_forced(&name)?;
+                }
+
+                self.file.get_project_mut(&path)?.name = name;
+
+                Ok(format!("Project renamed to {}.", text.to_titlecase()))
+            }
+            Input::Rename(Item::Task(path)) =>
+            {
+                let container = self.file.get_container(&path.container())?;
+
+                if container.get_task_forced(path.task_index)?.name != text
+                {
+                    container.task_exists_forced(text)?;
+                }
+
+                self.file.get_task_mut(&path)?.name = text.to_string();
+
+                Ok(format!("Task renamed to {}.", text.to_titlecase()))
+            }
+            Input::Due(path) =>
+            {
+                let due = match text.to_lowercase().as_str()
+                {
+                    "" | "none" => None,
+                    _ => Some(dates::parse_date(text)?),
+                };
+
+                self.file.get_task_mut(&path)?.due = due;
+
+                Ok(match due
+                {
+                    Some(due) =>
+                    {
+                        format!("Task due {}.", dates::format_date(&due))
+                    }
+                    None => "Due date removed.".to_string(),
+                })
+            }
+        }
+    }
+
+    /// Move an item to the top level of the list selected in the sidebar
+    fn move_item(&mut self, item: &Item) -> EResult<String>
+    {
+        let target = match self.current_list()
+        {
+            Some(list) => list.name.clone(),
+            None =>
+            {
+                return Err(error(io::ErrorKind::NotFound, "List not found."))
+            }
+        };
+
+        match item
+        {
+            Item::Task(path) =>
+            {
+                let container =
+                    self.file.get_container_mut(&path.container())?;
+
+                container.get_task_forced(path.task_index)?;
+
+                let task = container.remove_task(path.task_index);
+                let message = format!(
+                    "Moved task {} to {}.",
+                    task.name.to_titlecase(),
+                    target.to_titlecase()
+                );
+
+                self.file.get_list_mut_forced(&target)?.push_task(task);
+
+                Ok(message)
+            }
+            Item::Project(path) =>
+            {
+                let (parent, index) = match path.split_last()
+                {
+                    Some(location) => location,
+                    None =>
+                    {
+                        return Err(error(
+                            io::ErrorKind::NotFound,
+                            "Project not found.",
+                        ))
+                    }
+                };
+
+                let project = self
+                    .file
+                    .get_project_container_mut(&parent)?
+                    .remove_project(index);
+                let message = format!(
+                    "Moved project {} to {}.",
+                    project.name.to_titlecase(),
+                    target.to_titlecase()
+                );
+
+                self.file
+                    .get_list_mut_forced(&target)?
+                    .push_project(project);
+
+                Ok(message)
+            }
+        }
+    }
+
+    fn remove_item(&mut self, item: &Item) -> EResult<String>
+    {
+        match item
+        {
+            Item::Task(path) =>
+            {
+                let container =
+                    self.file.get_container_mut(&path.container())?;
+
+                container.get_task_forced(path.task_index)?;
+
+                let task = container.remove_task(path.task_index);
+
+                Ok(format!("Task {} removed.", task.name.to_titlecase()))
+            }
+            Item::Project(path) =>
+            {
+                let (parent, index) = match path.split_last()
+                {
+                    Some(location) => location,
+                    None =>
+                    {
+                        return Err(error(
+                            io::ErrorKind::NotFound,
+                            "Project not found.",
+                        ))
+                    }
+                };
+
+                self.file.get_project(path)?;
+
+                let project = self
+                    .file
+                    .get_project_container_mut(&parent)?
+                    .remove_project(index);
+
+                Ok(format!("Project {} removed.", project.name.to_titlecase()))
+            }
+        }
+    }
+
+    fn item_name(&self, item: &Item) -> String
+    {
+        match item
+        {
+            Item::Project(path) =>
+            {
+                self.file
+                    .get_project(path)
+                    .map(|project| project.name.clone())
+            }
+            Item::Task(path) =>
+            {
+                self.file.get_task(path).map(|task| task.name.clone())
+            }
+        }
+        .unwrap_or_default()
+    }
+
+    fn project_line(&self, project: &Project, depth: usize) -> Line<'static>
+    {
+        let status = project.status();
+
+        let mut spans = vec![
+            Span::raw("  ".repeat(depth)),
+            styled("▸", tos::palette().group),
+            Span::raw(" "),
+        ];
+
+        if let Some(priority) = &project.priority
+        {
+            spans.push(priority_span(priority));
+            spans.push(Span::raw(" "));
+        }
+
+        spans.push(
+            styled(project.name.to_titlecase(), item_color(&status)).bold(),
+        );
+        spans.push(Span::raw(" "));
+        spans.push(styled(
+            format!(
+                "{}/{}",
+                project.total_tasks_completed(),
+                project.total_tasks()
+            ),
+            tos::palette().number,
+        ));
+
+        if project.sequential
+        {
+            spans.push(styled(" (sequential)", tos::palette().info));
+        }
+
+        for tag in &project.tags
+        {
+            spans.push(styled(format!(" {}", tag), tos::palette().tag));
+        }
+
+        Line::from(spans)
+    }
+
+    fn task_line(
+        &self,
+        task: &Task,
+        depth: usize,
+        tasks_by_id: &TaskIndex,
+    ) -> Line<'static>
+    {
+        let blocked =
+            !task.done() && !tasks_by_id.open_blockers(task).is_empty();
+
+        let mut spans = vec![
+            Span::raw("  ".repeat(depth)),
+            status_span(&task.status),
+            Span::raw(" "),
+        ];
+
+        if let Some(priority) = &task.priority
+        {
+            spans.push(priority_span(priority));
+            spans.push(Span::raw(" "));
+        }
+
+        spans.push(styled(
+            task.name.to_titlecase(),
+            if blocked
+            {
+                tos::palette().blocked_item
+            }
+            else
+            {
+                item_color(&task.status)
+            },
+        ));
+
+        for context in &task.contexts
+        {
+            spans
+                .push(styled(format!(" {}", context), tos::palette().context));
+        }
+
+        for tag in &task.tags
+        {
+            spans.push(styled(format!(" {}", tag), tos::palette().tag));
+        }
+
+        if let Some(due) = &task.due
+        {
+            spans.push(Span::raw(" due "));
+            spans.push(styled(dates::format_date(due), tos::palette().date));
+        }
+
+        if let Some(person) = &task.delegate
+        {
+            spans.push(Span::raw(" → "));
+            spans.push(styled(person.clone(), tos::palette().delegate));
+        }
+
+        if !task.checklist.is_empty()
+        {
+            spans.push(styled(
+                format!(
+                    " [{}/{}]",
+                    task.checklist_completed(),
+                    task.checklist.len()
+                ),
+                tos::palette().number,
+            ));
+        }
+
+        Line::from(spans)
+    }
+
+    fn pane_block(&self, title: String, focus: Focus) -> Block<'static>
+    {
+        let border = if self.focus == focus
+        {
+            tos::palette().title
+        }
+        else
+        {
+            tos::palette().done_item
+        };
+
+        Block::bordered()
+            .title(styled(title, tos::palette().title).bold())
+            .border_style(Style::new().fg(color(border)))
+    }
+
+    fn draw(&mut self, frame: &mut Frame)
+    {
+        let [main_area, message_area, help_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let [lists_area, items_area] = Layout::horizontal([
+            Constraint::Length(SIDEBAR_WIDTH),
+            Constraint::Min(0),
+        ])
+        .areas(main_area);
+
+        let highlight = Style::new().add_modifier(Modifier::REVERSED);
+
+        let lists: Vec<ListItem> = self
+            .file
+            .lists
+            .iter()
+            .map(|list| {
+                let open = list.tasks().iter().filter(|t| !t.done()).count()
+                    + list
+                        .projects()
+                        .iter()
+                        .filter(|p| matches!(p.status(), Status::TODO))
+                        .count();
+
+                ListItem::new(Line::from(vec![
+                    styled(
+                        list.name.to_titlecase(),
+                        tos::palette().identifier,
+                    )
+                    .bold(),
+                    Span::raw(" "),
+                    styled(open.to_string(), tos::palette().number),
+                ]))
+            })
+            .collect();
+
+        let lists_title = match &self.mode
+        {
+            Mode::Moving { item, .. } =>
+            {
+                format!("Move {} to", self.item_name(item).to_titlecase())
+            }
+            _ => "Lists".to_string(),
+        };
+
+        frame.render_stateful_widget(
+            ListWidget::new(lists)
+                .block(self.pane_block(lists_title, Focus::Lists))
+                .highlight_style(highlight),
+            lists_area,
+            &mut self.lists,
+        );
+
+        let tasks_by_id = self.file.task_index();
+
+        let items: Vec<ListItem> = self
+            .rows()
+            .iter()
+            .filter_map(|row| {
+                match &row.item
+                {
+                    Item::Project(path) =>
+                    {
+                        self.file.get_project(path).ok().map(|project| {
+                            self.project_line(project, row.depth)
+                        })
+                    }
+                    Item::Task(path) =>
+                    {
+                        self.file.get_task(path).ok().map(|task| {
+                            self.task_line(task, row.depth, &tasks_by_id)
+                        })
+                    }
+                }
+            })
+            .map(ListItem::new)
+            .collect();
+
+        let items_title = match self.current_list()
+        {
+            Some(list) =>
+            {
+                match &list.kind
+                {
+                    Some(kind) =>
+                    {
+                        format!(
+                            "{} ({})",
+                            list.name.to_titlecase(),
+                            kind.label()
+                        )
+                    }
+                    None => list.name.to_titlecase(),
+                }
+            }
+            None => "No lists".to_string(),
+        };
+
+        let empty = items.is_empty();
+
+        frame.render_stateful_widget(
+            ListWidget::new(items)
+                .block(self.pane_block(items_title, Focus::Items))
+                .highlight_style(highlight),
+            items_area,
+            &mut self.items,
+        );
+
+        if empty
+        {
+            frame.render_widget(
+                Paragraph::new(styled(
+                    "  Nothing here yet: press a to add a task.",
+                    tos::palette().done_item,
+                )),
+                items_area.inner(ratatui::layout::Margin::new(1, 1)),
+            );
+        }
+
+        let message = match (&self.mode, &self.message)
+        {
+            (Mode::Removing(item), _) =>
+            {
+                Line::from(styled(
+                    format!(
+                        "Remove {}? (y/n)",
+                        self.item_name(item).to_titlecase()
+                    ),
+                    tos::palette().error,
+                ))
+            }
+            (_, Some(message)) =>
+            {
+                Line::from(styled(
+                    message.text.clone(),
+                    if message.error
+                    {
+                        tos::palette().error
+                    }
+                    else
+                    {
+                        tos::palette().success
+                    },
+                ))
+            }
+            _ => Line::default(),
+        };
+
+        frame.render_widget(Paragraph::new(message), message_area);
+
+        let help = match &self.mode
+        {
+            Mode::Typing { input, buffer } =>
+            {
+                Line::from(vec![
+                    styled(input_prompt(input), tos::palette().title).bold(),
+                    Span::raw(" "),
+                    Span::raw(buffer.clone()),
+                    Span::raw("█"),
+                ])
+            }
+            Mode::Moving { .. } =>
+            {
+                help_line(&[
+                    ("j/k", "choose list"),
+                    ("enter", "move"),
+                    ("esc", "cancel"),
+                ])
+            }
+            Mode::Removing(_) => Line::default(),
+            Mode::Normal if self.focus == Focus::Lists =>
+            {
+                help_line(&[
+                    ("j/k", "move"),
+                    ("enter", "open"),
+                    ("a", "task"),
+                    ("A", "project"),
+                    ("n", "new list"),
+                    ("e", "rename"),
+                    ("q", "quit"),
+                ])
+            }
+            Mode::Normal =>
+            {
+                help_line(&[
+                    ("j/k", "move"),
+                    ("space", "toggle"),
+                    ("a", "task"),
+                    ("A", "project"),
+                    ("e", "edit"),
+                    ("u", "due"),
+                    ("m", "move to"),
+                    ("d", "remove"),
+                    ("tab", "lists"),
+                    ("q", "quit"),
+                ])
+            }
+        };
+
+        frame.render_widget(Paragraph::new(help), help_area);
+    }
+}
+
+fn input_prompt(input: &Input) -> &'static str
+{
+    match input
+    {
+        Input::NewTask(_) => "New task:",
+        Input::NewProject(_) => "New project:",
+        Input::NewList => "New list:",
+        Input::Rename(_) | Input::RenameList(_) => "New name:",
+        Input::Due(_) => "Due date:",
+    }
+}
+
+fn help_line(keys: &[(&'static str, &'static str)]) -> Line<'static>
+{
+    let mut spans = vec![];
+
+    for (key, action) in keys
+    {
+        spans.push(styled(*key, tos::palette().identifier).bold());
+        spans.push(styled(format!(" {}  ", action), tos::palette().done_item));
+    }
+
+    Line::from(spans)
+}
+
+/// Browse and edit the workspace in a full-screen terminal interface, saving
+/// every change to `file_path` as it is made
+pub fn run(file: &mut File, file_path: &str) -> EResult<()>
+{
+    if !io::stdout().is_terminal()
+    {
+        return Err(error(
+            io::ErrorKind::Unsupported,
+            "The terminal interface needs an interactive terminal.",
+        ));
+    }
+
+    let mut terminal = ratatui::try_init()?;
+
+    let result = App::new(file, file_path).run(&mut terminal);
+
+    ratatui::restore();
+
+    result
+}